@@ -1,3 +1,80 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// 内嵌的资源目录
+const RESOURCES_DIR: &str = "resources";
+
+// 已经压缩过的格式直接存储，不再压缩，读取时还可以按位置分段读取
+const STORED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "ico", "woff", "woff2", "mp3", "mp4", "webm", "zip", "gz", "br",
+];
+
 fn main() {
+    // 启用 embedded-assets 特性时，把资源目录打包为 zip，由 protocol::embedded 通过 include_bytes! 内嵌
+    if std::env::var_os("CARGO_FEATURE_EMBEDDED_ASSETS").is_some() {
+        embed_resources();
+    }
     tauri_build::build()
 }
+
+fn embed_resources() {
+    println!("cargo:rerun-if-changed={}", RESOURCES_DIR);
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR 未设置"));
+    let output = out_dir.join("embedded-resources.zip");
+
+    let mut files = Vec::new();
+    collect_files(Path::new(RESOURCES_DIR), "", &mut files);
+    files.sort();
+
+    let mut zip = ZipWriter::new(File::create(&output).expect("无法创建内嵌资源包"));
+    for (name, path) in &files {
+        let stored = Path::new(name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| STORED_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()));
+        let options = if stored {
+            SimpleFileOptions::default().compression_method(CompressionMethod::Stored)
+        } else {
+            SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .compression_level(Some(9))
+        };
+        zip.start_file(name.as_str(), options).expect("写入内嵌资源包失败");
+        let mut file = File::open(path).unwrap_or_else(|e| panic!("读取 {:?} 失败: {}", path, e));
+        std::io::copy(&mut file, &mut zip).expect("写入内嵌资源包失败");
+    }
+    zip.finish().expect("写入内嵌资源包失败");
+
+    let built_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=EMBEDDED_ASSETS_BUILT_AT={}", built_at);
+    println!("cargo:warning=已内嵌 {} 个资源文件", files.len());
+}
+
+// 递归收集文件，路径以 "/" 分隔，跳过隐藏文件
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) {
+    let entries = std::fs::read_dir(dir).unwrap_or_else(|e| panic!("读取资源目录 {:?} 失败: {}", dir, e));
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, &relative, files);
+        } else {
+            files.push((relative, path));
+        }
+    }
+}
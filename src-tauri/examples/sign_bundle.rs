@@ -0,0 +1,51 @@
+// 项目包签名工具：为项目目录生成 integrity.json 和 integrity.sig，再打成 zip 即可通过 install_project 安装
+//
+//   cargo run --example sign_bundle -- <私钥文件> <项目目录>
+//   cargo run --example sign_bundle -- <私钥文件> --public-key
+//
+// 私钥文件内容为 Base64 编码的 32 字节随机种子，例如 `openssl rand -base64 32 > bundle-signing.key`
+// 对应的公钥写入 keys/bundle-signing.pub（或构建时的 BUNDLE_SIGNING_PUBLIC_KEY 环境变量）
+use std::path::Path;
+use std::process::ExitCode;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use tauri_app_test_lib::signing;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (key_path, target) = match args.as_slice() {
+        [key_path, target] => (key_path, target),
+        _ => {
+            eprintln!("用法: sign_bundle <私钥文件> <项目目录 | --public-key>");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let key = match std::fs::read_to_string(key_path)
+        .map_err(|e| format!("读取私钥失败 {}: {}", key_path, e))
+        .and_then(|content| signing::signing_key_from_base64(&content))
+    {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if target == "--public-key" {
+        println!("{}", BASE64.encode(key.verifying_key().to_bytes()));
+        return ExitCode::SUCCESS;
+    }
+
+    match signing::sign_bundle(Path::new(target), &key) {
+        Ok(manifest) => {
+            println!("🔏 已签名 {}: {} 个文件", target, manifest.files.len());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// 应用配置文件：应用配置目录下的 config.json，所有字段都可以省略
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+pub const CONFIG_FILE: &str = "config.json";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppConfig {
+    // 资源目录，相对路径相对于配置文件所在目录
+    pub resource_dir: Option<PathBuf>,
+    // 协议内存资源缓存的上限（MB），0 表示不缓存
+    pub asset_cache_size_mb: Option<u64>,
+}
+
+impl AppConfig {
+    // 读取配置文件，文件不存在时为 None
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        let content = match std::fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("读取配置文件失败 {:?}: {}", path, e)),
+        };
+        let mut config: AppConfig =
+            serde_json::from_slice(&content).map_err(|e| format!("解析配置文件失败 {:?}: {}", path, e))?;
+        config.resource_dir = config.resource_dir.map(|dir| match path.parent() {
+            Some(parent) if dir.is_relative() => parent.join(dir),
+            _ => dir,
+        });
+        Ok(Some(config))
+    }
+
+    // 资源缓存上限（字节），换算后超出 u64 范围时返回错误
+    pub fn asset_cache_capacity(&self) -> Result<Option<u64>, String> {
+        self.asset_cache_size_mb
+            .map(|size_mb| {
+                size_mb
+                    .checked_mul(1024 * 1024)
+                    .ok_or_else(|| format!("{} 中的 assetCacheSizeMb 过大: {}", CONFIG_FILE, size_mb))
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_resolves_relative_resource_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        assert!(AppConfig::load(&path).unwrap().is_none());
+
+        std::fs::write(&path, r#"{"resourceDir": "resources", "assetCacheSizeMb": 16}"#).unwrap();
+        let config = AppConfig::load(&path).unwrap().unwrap();
+        assert_eq!(config.resource_dir, Some(dir.path().join("resources")));
+        assert_eq!(config.asset_cache_size_mb, Some(16));
+
+        std::fs::write(&path, r#"{"resourceDir": "/opt/resources"}"#).unwrap();
+        let config = AppConfig::load(&path).unwrap().unwrap();
+        assert_eq!(config.resource_dir, Some(PathBuf::from("/opt/resources")));
+        assert_eq!(config.asset_cache_size_mb, None);
+    }
+
+    #[test]
+    fn malformed_config_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(AppConfig::load(&path).unwrap_err().contains("解析配置文件失败"));
+        std::fs::write(&path, r#"{"assetCacheSizeMb": "large"}"#).unwrap();
+        assert!(AppConfig::load(&path).is_err());
+    }
+
+    #[test]
+    fn asset_cache_capacity_checks_overflow() {
+        let config = |size_mb| AppConfig {
+            asset_cache_size_mb: size_mb,
+            ..AppConfig::default()
+        };
+        assert_eq!(config(None).asset_cache_capacity(), Ok(None));
+        assert_eq!(config(Some(0)).asset_cache_capacity(), Ok(Some(0)));
+        assert_eq!(config(Some(16)).asset_cache_capacity(), Ok(Some(16 * 1024 * 1024)));
+        assert!(config(Some(u64::MAX / 1024)).asset_cache_capacity().is_err());
+    }
+}
@@ -0,0 +1,681 @@
+// 项目自动发现：扫描资源目录和用户项目目录，包含入口 HTML（或 project.json 描述文件）的文件夹自动注册为项目
+// 单个 .zip / .tar 文件也可以是一个项目，直接从压缩包提供服务
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+use ed25519_dalek::VerifyingKey;
+
+use crate::install::{install_zip, InstallLimits};
+use crate::projects::{
+    confined_join, is_valid_project_id, Project, ProjectDescriptor, ProjectRegistry, ProjectSummary, ICON_FILE, MANIFEST_FILE,
+};
+use crate::protocol::{
+    archive, ArchiveFormat, AssetError, AssetSource, EmbeddedAssetSource, EmbeddedStore, ProtocolResolver,
+};
+use crate::signing::{self, IntegrityPolicy, INTEGRITY_FILE, SIGNATURE_FILE};
+use crate::versions::{ProjectVersion, ProjectVersions, DEFAULT_RETENTION};
+
+// 项目目录下的描述文件
+pub const DESCRIPTOR_FILE: &str = "project.json";
+
+// 构建工具生成的元数据文件（如 Expo 的 metadata.json），只读取其中的 name / description
+const METADATA_FILE: &str = "metadata.json";
+
+// 项目列表变化（重新扫描、安装）后发送，载荷为项目列表
+pub const CHANGED_EVENT: &str = "projects-changed";
+
+// 扫描目录，返回按 ID 排序的项目；目录不存在时为空
+// require_signature 为 true 时（用户项目目录）跳过没有有效签名的文件夹和压缩包
+pub fn discover(dir: &Path, require_signature: bool) -> Vec<Project> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                println!("⚠️ 扫描项目目录失败 {:?}: {}", dir, e);
+            }
+            return Vec::new();
+        }
+    };
+
+    let mut projects: Vec<Project> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        // 跳过隐藏目录（包括安装时的临时目录）
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .filter_map(|path| {
+            let result = if path.is_dir() {
+                discover_project(&path, require_signature)
+            } else if ArchiveFormat::from_path(&path).is_some() {
+                discover_archive(&path, require_signature)
+            } else {
+                Ok(None)
+            };
+            result.unwrap_or_else(|e| {
+                println!("⚠️ 跳过项目 {:?}: {}", path, e);
+                None
+            })
+        })
+        .collect();
+    projects.sort_by(|a, b| a.id.cmp(&b.id));
+    projects
+}
+
+// 识别单个文件夹：有 project.json 时按描述注册，否则要求存在 index.html
+// 带版本的项目目录（有 versions.json）使用当前版本的目录
+// 不是项目的文件夹（如 mocks）返回 Ok(None)
+pub fn discover_project(folder: &Path, require_signature: bool) -> Result<Option<Project>, String> {
+    if ProjectVersions::is_versioned(folder) {
+        return discover_versioned(folder, require_signature);
+    }
+    let base_dir = folder.parent().unwrap_or(folder);
+    discover_folder(folder, base_dir, None, require_signature)
+}
+
+// 带版本的项目：ID 取项目目录名，版本号取当前版本的目录名
+fn discover_versioned(folder: &Path, require_signature: bool) -> Result<Option<Project>, String> {
+    let versions = ProjectVersions::open(folder)?;
+    let (Some(version), Some(version_dir)) = (versions.active(), versions.active_dir()) else {
+        return Ok(None);
+    };
+    let id = folder
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let base_dir = folder.parent().unwrap_or(folder);
+    discover_folder(&version_dir, base_dir, Some((id, version.to_string())), require_signature)
+}
+
+fn discover_folder(
+    folder: &Path,
+    base_dir: &Path,
+    versioned: Option<(String, String)>,
+    require_signature: bool,
+) -> Result<Option<Project>, String> {
+    discover_signed_folder(folder, base_dir, versioned, require_signature, &signing::trusted_key()?)
+}
+
+// 先校验签名清单，再读取 project.json：有清单时描述文件和模拟后端文件都必须与清单一致
+fn discover_signed_folder(
+    folder: &Path,
+    base_dir: &Path,
+    versioned: Option<(String, String)>,
+    require_signature: bool,
+    key: &VerifyingKey,
+) -> Result<Option<Project>, String> {
+    // 带签名清单的项目包：签名必须有效，否则不注册；策略要求签名时没有清单也不注册
+    let manifest = if folder.join(INTEGRITY_FILE).is_file() {
+        signing::load_manifest(folder, key)?
+    } else {
+        None
+    };
+    if manifest.is_none() && require_signature {
+        return Err("项目包没有签名".to_string());
+    }
+
+    let descriptor_path = folder.join(DESCRIPTOR_FILE);
+    let mut descriptor = match std::fs::read(&descriptor_path) {
+        Ok(content) => {
+            if let Some(manifest) = &manifest {
+                manifest
+                    .file_hashes(folder, folder)
+                    .matches(DESCRIPTOR_FILE, &content)
+                    .map_err(|e| format!("{:?}: {}", folder, e))?;
+            }
+            serde_json::from_slice::<ProjectDescriptor>(&content)
+                .map_err(|e| format!("解析 {:?} 失败: {}", descriptor_path, e))?
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => ProjectDescriptor::default(),
+        Err(e) => return Err(format!("读取 {:?} 失败: {}", descriptor_path, e)),
+    };
+
+    // project.json 来自项目包，root 和 entry 不能越出项目目录
+    let root = match &descriptor.root {
+        Some(root) => confined_join(folder, root)?,
+        None => folder.to_path_buf(),
+    };
+    if !confined_join(&root, Path::new(&descriptor.entry))?.is_file() {
+        return Ok(None);
+    }
+
+    if let Some((id, version)) = versioned {
+        descriptor.id = id;
+        descriptor.version = Some(version);
+    }
+    if descriptor.id.is_empty() {
+        descriptor.id = folder
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+    }
+    if !is_valid_project_id(&descriptor.id) {
+        return Err(format!("无效的项目 ID {:?}：只允许小写字母、数字和 \"-\"", descriptor.id));
+    }
+
+    if descriptor.name.is_none() || descriptor.description.is_empty() {
+        fill_from_metadata(&mut descriptor, std::fs::read(root.join(METADATA_FILE)).ok());
+    }
+
+    descriptor.root = Some(root.clone());
+    let mut project = Project::from_descriptor(descriptor, base_dir, folder)?;
+    if let Some(manifest) = &manifest {
+        match project.mock_dir.take() {
+            // 项目包内的模拟后端：所有文件都必须在清单中且内容一致
+            Some(mock_dir) if mock_dir.starts_with(folder) => {
+                manifest.verify_dir(folder, &mock_dir)?;
+                project.mock_integrity = Some(manifest.file_hashes(folder, &mock_dir));
+                project.mock_dir = Some(mock_dir);
+            }
+            // 项目包外的模拟后端不在签名范围内，不加载
+            Some(mock_dir) => println!("⚠️ 项目 {} 的模拟后端不在签名范围内，忽略: {:?}", project.id, mock_dir),
+            None => {}
+        }
+    }
+    project.integrity = manifest.map(|manifest| manifest.file_hashes(folder, &root));
+    project.require_signature = require_signature;
+    Ok(Some(project))
+}
+
+// 单文件项目（studio.zip / studio.tar）：描述文件和入口文件从压缩包中读取，ID 默认取文件名
+// 压缩包本身就是项目根目录，描述文件中的 root 不起作用
+pub fn discover_archive(path: &Path, require_signature: bool) -> Result<Option<Project>, String> {
+    let source = archive::open_source(path)?;
+    let default_id = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let base_dir = path.parent().unwrap_or(path);
+    discover_source(source.as_ref(), &default_id, path, base_dir, require_signature)
+}
+
+// 从资源来源（压缩包、内嵌资源）识别项目，root 为项目根目录的路径
+// 签名清单和签名位于来源的根目录，规则与文件夹项目相同
+fn discover_source(
+    source: &dyn AssetSource,
+    default_id: &str,
+    path: &Path,
+    base_dir: &Path,
+    require_signature: bool,
+) -> Result<Option<Project>, String> {
+    let manifest = match source.read(INTEGRITY_FILE) {
+        Ok(content) => {
+            let signature = source
+                .read(SIGNATURE_FILE)
+                .map_err(|e| format!("读取 {:?} 中的 {} 失败: {}", path, SIGNATURE_FILE, e))?;
+            Some(signing::verify_manifest(&content, &signature, &signing::trusted_key()?)?)
+        }
+        Err(AssetError::NotFound) => None,
+        Err(e) => return Err(format!("读取 {:?} 中的 {} 失败: {}", path, INTEGRITY_FILE, e)),
+    };
+    if manifest.is_none() && require_signature {
+        return Err("项目包没有签名".to_string());
+    }
+    // 清单中的路径就是压缩包内的路径
+    let integrity = manifest.map(|manifest| manifest.file_hashes(path, path));
+
+    let mut descriptor = match source.read(DESCRIPTOR_FILE) {
+        Ok(content) => {
+            if let Some(integrity) = &integrity {
+                integrity
+                    .matches(DESCRIPTOR_FILE, &content)
+                    .map_err(|e| format!("{:?}: {}", path, e))?;
+            }
+            serde_json::from_slice::<ProjectDescriptor>(&content)
+                .map_err(|e| format!("解析 {:?} 中的 {} 失败: {}", path, DESCRIPTOR_FILE, e))?
+        }
+        Err(AssetError::NotFound) => ProjectDescriptor::default(),
+        Err(e) => return Err(format!("读取 {:?} 中的 {} 失败: {}", path, DESCRIPTOR_FILE, e)),
+    };
+    if source.metadata(&descriptor.entry).is_err() {
+        return Ok(None);
+    }
+
+    if descriptor.id.is_empty() {
+        descriptor.id = default_id.to_string();
+    }
+    if !is_valid_project_id(&descriptor.id) {
+        return Err(format!("无效的项目 ID {:?}：只允许小写字母、数字和 \"-\"", descriptor.id));
+    }
+    if descriptor.name.is_none() || descriptor.description.is_empty() {
+        fill_from_metadata(&mut descriptor, source.read(METADATA_FILE).ok());
+    }
+    if descriptor.icon.is_none() && source.metadata(ICON_FILE).is_ok() {
+        descriptor.icon = Some(ICON_FILE.to_string());
+    }
+
+    descriptor.root = Some(path.to_path_buf());
+    // 压缩包中的 mockDir 相对于压缩包本身，只能指向包内（文件系统中不存在，不会加载）
+    let mut project = Project::from_descriptor(descriptor, base_dir, path)?;
+    // 模拟后端目录在压缩包外，不在签名范围内，签名的项目包不加载
+    if integrity.is_some() {
+        if let Some(mock_dir) = project.mock_dir.take() {
+            println!("⚠️ 项目 {} 的模拟后端不在签名范围内，忽略: {:?}", project.id, mock_dir);
+        }
+    }
+    project.integrity = integrity;
+    project.require_signature = require_signature;
+    Ok(Some(project))
+}
+
+// 从内嵌资源包构建注册表：与资源目录相同，先加载清单，再识别其余的顶层目录
+// 项目路径仍以 resource_dir 为基准，只用于日志和 ID 去重，文件全部从资源包读取
+pub fn embedded_registry(store: &Arc<EmbeddedStore>, resource_dir: &Path) -> ProjectRegistry {
+    let mut registry = match store.read(MANIFEST_FILE) {
+        Ok(content) => ProjectRegistry::parse_manifest(&content, &resource_dir.join(MANIFEST_FILE))
+            .unwrap_or_else(|e| {
+                println!("⚠️ {}", e);
+                ProjectRegistry::default()
+            }),
+        Err(_) => ProjectRegistry::default(),
+    };
+    for project in registry.projects().to_vec() {
+        let prefix = project.root.strip_prefix(resource_dir).unwrap_or(&project.root);
+        let source = EmbeddedAssetSource::new(store.clone(), &prefix.to_string_lossy());
+        let mut project = project;
+        if project.icon.is_none() && source.metadata(ICON_FILE).is_ok() {
+            project.icon = Some(ICON_FILE.to_string());
+        }
+        project.embedded = Some(source);
+        registry.register(project);
+    }
+
+    for dir in store.top_level_dirs() {
+        let root = resource_dir.join(&dir);
+        let source = EmbeddedAssetSource::new(store.clone(), &dir);
+        match discover_source(&source, &dir, &root, resource_dir, false) {
+            Ok(Some(mut project)) => {
+                let known = registry
+                    .projects()
+                    .iter()
+                    .any(|p| p.id == project.id || p.root == project.root);
+                if !known {
+                    println!("🔍 发现内嵌项目: {}", project.id);
+                    project.embedded = Some(source);
+                    registry.register(project);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => println!("⚠️ 跳过内嵌项目 {}: {}", dir, e),
+        }
+    }
+    registry
+}
+
+// 描述文件没有写名称、描述时，尝试从构建元数据中读取
+fn fill_from_metadata(descriptor: &mut ProjectDescriptor, metadata: Option<Vec<u8>>) {
+    let metadata = metadata.and_then(|content| serde_json::from_slice::<serde_json::Value>(&content).ok());
+    let field = |key: &str| {
+        metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(key))
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+    };
+    if descriptor.name.is_none() {
+        descriptor.name = field("name");
+    }
+    if descriptor.description.is_empty() {
+        descriptor.description = field("description").unwrap_or_default();
+    }
+}
+
+// 项目目录：资源目录（内置项目和清单）+ 用户项目目录
+pub struct ProjectCatalog {
+    resource_dir: PathBuf,
+    user_dir: Option<PathBuf>,
+    // 每个已安装项目保留的版本数（包括当前版本）
+    retention: usize,
+    // 安装和服务时的签名校验策略
+    integrity: IntegrityPolicy,
+    // 内嵌资源包（embedded-assets 构建），存在时内置项目从这里读取，不再扫描资源目录
+    embedded: Option<Arc<EmbeddedStore>>,
+    registry: RwLock<ProjectRegistry>,
+}
+
+impl ProjectCatalog {
+    pub fn new(resource_dir: PathBuf, user_dir: Option<PathBuf>) -> Self {
+        let catalog = Self {
+            resource_dir,
+            user_dir,
+            retention: DEFAULT_RETENTION,
+            integrity: IntegrityPolicy::default(),
+            embedded: EmbeddedStore::bundled(),
+            registry: RwLock::new(ProjectRegistry::default()),
+        };
+        *catalog.registry.write().unwrap() = catalog.scan();
+        catalog
+    }
+
+    pub fn with_retention(mut self, retention: usize) -> Self {
+        self.retention = retention.max(1);
+        self
+    }
+
+    // 更换签名校验策略，已注册的项目按新策略重新挂载
+    pub fn with_integrity(mut self, integrity: IntegrityPolicy) -> Self {
+        self.integrity = integrity;
+        *self.registry.get_mut().unwrap() = self.scan();
+        self
+    }
+
+    // 使用指定的内嵌资源包代替资源目录
+    pub fn with_embedded(mut self, store: Arc<EmbeddedStore>) -> Self {
+        self.embedded = Some(store);
+        *self.registry.get_mut().unwrap() = self.scan();
+        self
+    }
+
+    pub fn resource_dir(&self) -> &Path {
+        &self.resource_dir
+    }
+
+    pub fn user_dir(&self) -> Option<&Path> {
+        self.user_dir.as_deref()
+    }
+
+    pub fn registry(&self) -> RwLockReadGuard<'_, ProjectRegistry> {
+        self.registry.read().unwrap()
+    }
+
+    pub fn summaries(&self) -> Vec<ProjectSummary> {
+        self.registry()
+            .projects()
+            .iter()
+            .map(|project| project.summary())
+            .collect()
+    }
+
+    // 构建注册表：清单中的项目优先于资源目录中发现的项目，用户目录中安装的项目覆盖两者
+    pub fn scan(&self) -> ProjectRegistry {
+        let mut registry = match &self.embedded {
+            Some(store) => embedded_registry(store, &self.resource_dir),
+            None => self.scan_resource_dir(),
+        };
+
+        // 用户安装的项目覆盖同 ID 的内置项目，便于在不重新打包的情况下更新
+        if let Some(user_dir) = &self.user_dir {
+            for project in discover(user_dir, self.integrity.require_signature) {
+                println!("🔍 发现已安装项目: {} -> {:?}", project.id, project.root);
+                registry.register(project);
+            }
+        }
+        registry.verify_files(self.integrity.verify_on_serve)
+    }
+
+    fn scan_resource_dir(&self) -> ProjectRegistry {
+        let manifest_path = self.resource_dir.join(MANIFEST_FILE);
+        let mut registry = if manifest_path.is_file() {
+            ProjectRegistry::load_manifest(&manifest_path).unwrap_or_else(|e| {
+                println!("⚠️ {}", e);
+                ProjectRegistry::default()
+            })
+        } else {
+            ProjectRegistry::default()
+        };
+
+        for project in discover(&self.resource_dir, false) {
+            let known = registry
+                .projects()
+                .iter()
+                .any(|p| p.id == project.id || p.root == project.root);
+            if !known {
+                println!("🔍 发现项目: {} -> {:?}", project.id, project.root);
+                registry.register(project);
+            }
+        }
+        registry
+    }
+
+    // 安装 zip 项目包到用户项目目录，然后重新扫描，返回安装的项目
+    pub fn install(
+        &self,
+        archive_path: &Path,
+        limits: &InstallLimits,
+        resolver: &ProtocolResolver,
+    ) -> Result<ProjectSummary, String> {
+        let user_dir = self.user_dir.as_deref().ok_or("没有可用的用户项目目录")?;
+        let project = install_zip(archive_path, user_dir, limits, self.retention, &self.integrity)?;
+        self.rescan(resolver);
+        Ok(project.summary())
+    }
+
+    // 已安装项目的所有版本
+    pub fn list_versions(&self, project_id: &str) -> Result<Vec<ProjectVersion>, String> {
+        Ok(self.versions(project_id)?.list())
+    }
+
+    // 切换到指定版本，立即生效
+    pub fn activate_version(
+        &self,
+        project_id: &str,
+        version: &str,
+        resolver: &ProtocolResolver,
+    ) -> Result<ProjectSummary, String> {
+        self.versions(project_id)?.activate(version)?;
+        println!("🔀 项目 {} 切换到版本 {}", project_id, version);
+        self.reload_project(project_id, resolver)
+    }
+
+    // 回滚到上一个激活过的版本，立即生效
+    pub fn rollback(&self, project_id: &str, resolver: &ProtocolResolver) -> Result<ProjectSummary, String> {
+        let version = self.versions(project_id)?.rollback()?;
+        println!("⏪ 项目 {} 回滚到版本 {}", project_id, version);
+        self.reload_project(project_id, resolver)
+    }
+
+    fn versions(&self, project_id: &str) -> Result<ProjectVersions, String> {
+        let user_dir = self.user_dir.as_deref().ok_or("没有可用的用户项目目录")?;
+        let project_dir = user_dir.join(project_id);
+        if !is_valid_project_id(project_id) || !ProjectVersions::is_versioned(&project_dir) {
+            return Err(format!("项目 {} 没有已安装的版本", project_id));
+        }
+        ProjectVersions::open(&project_dir)
+    }
+
+    // 只重新识别一个项目并替换它的挂载，不影响其他项目
+    fn reload_project(&self, project_id: &str, resolver: &ProtocolResolver) -> Result<ProjectSummary, String> {
+        let user_dir = self.user_dir.as_deref().ok_or("没有可用的用户项目目录")?;
+        let project_dir = user_dir.join(project_id);
+        let project = discover_project(&project_dir, self.integrity.require_signature)?
+            .ok_or_else(|| format!("无法识别项目 {:?}", project_dir))?;
+        let summary = project.summary();
+        resolver.replace_project(project.id.clone(), project.mount(self.integrity.verify_on_serve));
+        self.registry.write().unwrap().register(project);
+        Ok(summary)
+    }
+
+    // 按注册表中的项目重新挂载（压缩包项目的索引在打开时建立，文件替换后需要重建）
+    pub fn remount(&self, project_id: &str, resolver: &ProtocolResolver) -> Result<(), String> {
+        let registry = self.registry();
+        let project = registry
+            .get(project_id)
+            .ok_or_else(|| format!("未知项目: {}", project_id))?;
+        resolver.replace_project(project.id.clone(), project.mount(self.integrity.verify_on_serve));
+        Ok(())
+    }
+
+    // 重新扫描并替换注册表，协议解析器随之更新，返回新的项目列表
+    pub fn rescan(&self, resolver: &ProtocolResolver) -> Vec<ProjectSummary> {
+        let registry = self.scan();
+        resolver.replace_projects(registry.mounts());
+        *self.registry.write().unwrap() = registry;
+        self.summaries()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 在 dir 下创建项目文件夹，descriptor 为 project.json 的内容
+    fn project(dir: &Path, name: &str, descriptor: Option<&str>, files: &[&str]) -> PathBuf {
+        let folder = dir.join(name);
+        std::fs::create_dir_all(&folder).unwrap();
+        if let Some(descriptor) = descriptor {
+            std::fs::write(folder.join(DESCRIPTOR_FILE), descriptor).unwrap();
+        }
+        for file in files {
+            let path = folder.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "<html></html>").unwrap();
+        }
+        folder
+    }
+
+    fn ids(projects: &[Project]) -> Vec<&str> {
+        projects.iter().map(|project| project.id.as_str()).collect()
+    }
+
+    #[test]
+    fn descriptor_root_and_mock_dir_stay_inside() {
+        let dir = tempfile::tempdir().unwrap();
+        project(dir.path(), "plain", None, &["index.html"]);
+        let descriptor = r#"{"root": "dist", "mockDir": "mocks"}"#;
+        let nested = project(dir.path(), "nested", Some(descriptor), &["dist/index.html"]);
+        let other = project(dir.path(), "other", Some(r#"{"mockDir": "plain"}"#), &["index.html"]);
+
+        let projects = discover(dir.path(), false);
+        assert_eq!(ids(&projects), ["nested", "other", "plain"]);
+        assert_eq!(projects[0].root, nested.join("dist"));
+        // mockDir 相对于项目文件夹，而不是项目所在的目录，不会指向其他项目
+        assert_eq!(projects[0].mock_dir, Some(nested.join("mocks")));
+        assert_eq!(projects[1].mock_dir, Some(other.join("plain")));
+    }
+
+    #[test]
+    fn hostile_descriptor_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("index.html"), "<html></html>").unwrap();
+        let outside_root = serde_json::to_string(outside.path()).unwrap();
+
+        project(dir.path(), "good", None, &["index.html"]);
+        project(dir.path(), "absolute", Some(&format!(r#"{{"root": {}}}"#, outside_root)), &["index.html"]);
+        project(dir.path(), "root-slash", Some(r#"{"root": "/"}"#), &["index.html"]);
+        project(dir.path(), "parent", Some(r#"{"root": "../good"}"#), &[]);
+        project(dir.path(), "entry", Some(r#"{"entry": "../good/index.html"}"#), &[]);
+        project(dir.path(), "mocks", Some(r#"{"mockDir": "../../etc"}"#), &["index.html"]);
+        project(dir.path(), "sibling", Some(r#"{"mockDir": "../good"}"#), &["index.html"]);
+        #[cfg(unix)]
+        {
+            let folder = project(dir.path(), "symlink", Some(r#"{"root": "dist"}"#), &[]);
+            std::os::unix::fs::symlink(outside.path(), folder.join("dist")).unwrap();
+        }
+
+        assert_eq!(ids(&discover(dir.path(), false)), ["good"]);
+        assert!(discover_project(&dir.path().join("root-slash"), false).is_err());
+        assert!(discover_project(&dir.path().join("mocks"), false).is_err());
+        assert!(discover_project(&dir.path().join("sibling"), false).is_err());
+    }
+
+    #[test]
+    fn confined_join_rejects_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(confined_join(dir.path(), Path::new("a/./b")).unwrap(), dir.path().join("a/b"));
+        for path in ["/", "/etc", "..", "a/../../b", "a/.."] {
+            assert!(confined_join(dir.path(), Path::new(path)).is_err(), "{}", path);
+        }
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &str)]) {
+        use std::io::Write;
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        for (name, content) in files {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn unsigned_user_projects_are_skipped_when_required() {
+        let resources = tempfile::tempdir().unwrap();
+        let user_dir = tempfile::tempdir().unwrap();
+        project(resources.path(), "bundled", None, &["index.html"]);
+        project(user_dir.path(), "folder", None, &["index.html"]);
+        write_zip(&user_dir.path().join("packed.zip"), &[("index.html", "<html></html>")]);
+
+        assert_eq!(ids(&discover(user_dir.path(), false)), ["folder", "packed"]);
+        assert!(discover(user_dir.path(), true).is_empty());
+
+        // 内置项目不要求签名
+        let required = IntegrityPolicy {
+            require_signature: true,
+            verify_on_serve: true,
+        };
+        let catalog = ProjectCatalog::new(resources.path().to_path_buf(), Some(user_dir.path().to_path_buf()))
+            .with_integrity(required);
+        assert_eq!(ids(catalog.registry().projects()), ["bundled"]);
+    }
+
+    #[test]
+    fn untrusted_signature_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = project(dir.path(), "untrusted", None, &["index.html"]);
+        let key = ed25519_dalek::SigningKey::from_bytes(&[9; 32]);
+        signing::sign_bundle(&folder, &key).unwrap();
+        assert!(discover_project(&folder, false).is_err());
+
+        let manifest = std::fs::read_to_string(folder.join(INTEGRITY_FILE)).unwrap();
+        let signature = std::fs::read_to_string(folder.join(SIGNATURE_FILE)).unwrap();
+        let archive = dir.path().join("untrusted.zip");
+        write_zip(
+            &archive,
+            &[("index.html", "<html></html>"), (INTEGRITY_FILE, &manifest), (SIGNATURE_FILE, &signature)],
+        );
+        assert!(discover_archive(&archive, false).is_err());
+        assert!(discover(dir.path(), false).is_empty());
+    }
+
+    #[test]
+    fn unsigned_project_is_not_served_when_required() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = project(dir.path(), "plain", None, &["index.html"]);
+        let mut project = discover_project(&folder, false).unwrap().unwrap();
+        project.require_signature = true;
+
+        let resolver = ProtocolResolver::new();
+        resolver.replace_projects(std::collections::HashMap::from([(project.id.clone(), project.mount(false))]));
+        let request = tauri::http::Request::builder()
+            .uri("myapp://plain/index.html")
+            .body(Vec::new())
+            .unwrap();
+        assert_eq!(resolver.resolve(&request).status(), 403);
+    }
+
+    #[test]
+    fn signed_descriptor_and_mocks_must_match_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let descriptor = r#"{"root": "dist", "mockDir": "mocks"}"#;
+        let folder = project(dir.path(), "signed", Some(descriptor), &["dist/index.html"]);
+        std::fs::create_dir_all(folder.join("mocks/routes")).unwrap();
+        std::fs::write(folder.join("mocks/routes/api.json"), r#"[{"path": "/api/ping", "body": "pong"}]"#).unwrap();
+        let key = ed25519_dalek::SigningKey::from_bytes(&[9; 32]);
+        signing::sign_bundle(&folder, &key).unwrap();
+        let discover_signed = || discover_signed_folder(&folder, dir.path(), None, true, &key.verifying_key());
+
+        let project = discover_signed().unwrap().unwrap();
+        assert_eq!(project.root, folder.join("dist"));
+        assert_eq!(project.mock_dir, Some(folder.join("mocks")));
+        assert!(project.mock_integrity.as_ref().is_some_and(|hashes| hashes.get("routes/api.json").is_some()));
+
+        // 签名后修改 project.json（例如把 root 指向其他目录）
+        std::fs::write(folder.join(DESCRIPTOR_FILE), r#"{"root": "dist", "mockDir": "mocks", "proxy": []}"#).unwrap();
+        let error = discover_signed().unwrap_err();
+        assert!(error.contains(DESCRIPTOR_FILE), "{}", error);
+
+        std::fs::write(folder.join(DESCRIPTOR_FILE), descriptor).unwrap();
+        assert!(discover_signed().is_ok());
+
+        // 签名后修改或新增模拟后端文件
+        std::fs::write(folder.join("mocks/routes/api.json"), r#"[{"path": "/api/ping", "body": "evil"}]"#).unwrap();
+        assert!(discover_signed().is_err());
+        std::fs::write(folder.join("mocks/routes/api.json"), r#"[{"path": "/api/ping", "body": "pong"}]"#).unwrap();
+        std::fs::write(folder.join("mocks/routes/extra.json"), "[]").unwrap();
+        let error = discover_signed().unwrap_err();
+        assert!(error.contains("routes/extra.json"), "{}", error);
+    }
+}
@@ -0,0 +1,459 @@
+// 运行时安装项目：校验并解压项目压缩包到用户项目目录的版本目录，解压完成后切换为当前版本
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::discovery::{discover_project, DESCRIPTOR_FILE};
+use crate::projects::{confined_join, is_valid_project_id, Project, ProjectDescriptor};
+use crate::protocol::DEFAULT_DOCUMENT;
+use crate::signing::{self, IntegrityPolicy};
+use crate::versions::{is_valid_version, ProjectVersions};
+
+// 压缩包大小限制，防止压缩炸弹和误选的大文件
+#[derive(Debug, Clone)]
+pub struct InstallLimits {
+    // 压缩包文件本身
+    pub max_archive_size: u64,
+    // 条目数量
+    pub max_entries: usize,
+    // 单个文件解压后
+    pub max_file_size: u64,
+    // 全部文件解压后
+    pub max_total_size: u64,
+}
+
+impl Default for InstallLimits {
+    fn default() -> Self {
+        Self {
+            max_archive_size: 512 * 1024 * 1024,
+            max_entries: 20_000,
+            max_file_size: 256 * 1024 * 1024,
+            max_total_size: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+// 安装 zip 压缩包到 projects_dir/{id}/{version} 并设为当前版本，超出 retention 的旧版本会被删除
+// 项目 ID 取自压缩包内的 project.json，没有时取压缩包文件名；版本号没有时按安装时间生成
+// 压缩包内所有文件只有一个顶层目录时（如 studio/index.html），以该目录为项目根目录
+// 带签名清单的项目包必须签名有效且文件完整；策略要求签名时拒绝未签名的项目包
+pub fn install_zip(
+    archive_path: &Path,
+    projects_dir: &Path,
+    limits: &InstallLimits,
+    retention: usize,
+    integrity: &IntegrityPolicy,
+) -> Result<Project, String> {
+    let archive_size = std::fs::metadata(archive_path)
+        .map_err(|e| format!("读取压缩包失败 {:?}: {}", archive_path, e))?
+        .len();
+    if archive_size > limits.max_archive_size {
+        return Err(format!(
+            "压缩包过大: {} 字节（上限 {} 字节）",
+            archive_size, limits.max_archive_size
+        ));
+    }
+
+    std::fs::create_dir_all(projects_dir)
+        .map_err(|e| format!("创建项目目录失败 {:?}: {}", projects_dir, e))?;
+
+    // 先解压到同一目录下的临时目录，保证最后的 rename 是同一文件系统内的原子操作
+    let staging = projects_dir.join(format!(".staging-{}", unique_suffix()));
+    let result = extract_zip(archive_path, &staging, limits).and_then(|root| {
+        let (id, version) = validate(&root, archive_path)?;
+        verify_signature(&root, integrity)?;
+        let project_dir = projects_dir.join(&id);
+        std::fs::create_dir_all(&project_dir)
+            .map_err(|e| format!("创建项目目录失败 {:?}: {}", project_dir, e))?;
+        let mut versions = ProjectVersions::open(&project_dir)?;
+        replace_dir(&root, &versions.version_dir(&version))?;
+        versions.activate(&version)?;
+        versions.gc(retention)?;
+        discover_project(&project_dir, integrity.require_signature)?
+            .ok_or_else(|| format!("安装后无法识别项目 {:?}", project_dir))
+    });
+    if staging.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&staging) {
+            println!("⚠️ 清理临时目录失败 {:?}: {}", staging, e);
+        }
+    }
+
+    let project = result?;
+    println!(
+        "📦 项目已安装: {} {} -> {:?}",
+        project.id,
+        project.version.as_deref().unwrap_or_default(),
+        project.root
+    );
+    Ok(project)
+}
+
+// 解压到 staging，返回项目根目录（staging 或其中唯一的顶层目录）
+fn extract_zip(archive_path: &Path, staging: &Path, limits: &InstallLimits) -> Result<PathBuf, String> {
+    let file = File::open(archive_path).map_err(|e| format!("打开压缩包失败 {:?}: {}", archive_path, e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("无效的 zip 压缩包: {}", e))?;
+    if archive.len() > limits.max_entries {
+        return Err(format!("压缩包条目过多: {}（上限 {}）", archive.len(), limits.max_entries));
+    }
+
+    std::fs::create_dir_all(staging).map_err(|e| format!("创建临时目录失败 {:?}: {}", staging, e))?;
+
+    let mut total_size: u64 = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("读取压缩包条目失败: {}", e))?;
+        let name = entry.name().to_string();
+
+        // zip-slip：拒绝绝对路径、".." 以及符号链接
+        let relative = safe_entry_path(&name).ok_or_else(|| format!("压缩包包含不安全的路径: {}", name))?;
+        if entry.is_symlink() {
+            return Err(format!("压缩包包含符号链接: {}", name));
+        }
+        let target = staging.join(&relative);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target).map_err(|e| format!("创建目录失败 {:?}: {}", target, e))?;
+            continue;
+        }
+
+        // 声明的大小可以伪造，这里先检查一次，写入时再按实际字节数限制
+        if entry.size() > limits.max_file_size {
+            return Err(format!("文件过大: {}（{} 字节）", name, entry.size()));
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败 {:?}: {}", parent, e))?;
+        }
+        let remaining = limits.max_total_size.saturating_sub(total_size);
+        let allowed = limits.max_file_size.min(remaining);
+        let mut output = File::create(&target).map_err(|e| format!("创建文件失败 {:?}: {}", target, e))?;
+        let written = std::io::copy(&mut (&mut entry).take(allowed + 1), &mut output)
+            .map_err(|e| format!("解压失败 {}: {}", name, e))?;
+        if written > allowed {
+            return Err(if allowed == remaining {
+                format!("解压后总大小超过上限 {} 字节", limits.max_total_size)
+            } else {
+                format!("文件过大: {}", name)
+            });
+        }
+        total_size += written;
+    }
+
+    Ok(single_top_level_dir(staging).unwrap_or_else(|| staging.to_path_buf()))
+}
+
+// 条目路径只能由普通路径段组成
+fn safe_entry_path(name: &str) -> Option<PathBuf> {
+    if name.contains('\0') || name.contains('\\') {
+        return None;
+    }
+    let path = Path::new(name);
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    if relative.as_os_str().is_empty() {
+        None
+    } else {
+        Some(relative)
+    }
+}
+
+// 根目录下没有入口文件和描述文件、只有一个子目录时，返回该子目录
+fn single_top_level_dir(dir: &Path) -> Option<PathBuf> {
+    if dir.join(DEFAULT_DOCUMENT).exists() || dir.join(DESCRIPTOR_FILE).exists() {
+        return None;
+    }
+    let mut entries = std::fs::read_dir(dir).ok()?.filter_map(|entry| entry.ok());
+    let only = entries.next()?;
+    if entries.next().is_some() || !only.path().is_dir() {
+        return None;
+    }
+    Some(only.path())
+}
+
+// 替换前校验：入口文件必须存在且位于包内；返回项目 ID（描述文件中的 id，其次是压缩包文件名）和版本号
+fn validate(root: &Path, archive_path: &Path) -> Result<(String, String), String> {
+    let descriptor_path = root.join(DESCRIPTOR_FILE);
+    let descriptor = match std::fs::read_to_string(&descriptor_path) {
+        Ok(content) => serde_json::from_str::<ProjectDescriptor>(&content)
+            .map_err(|e| format!("解析 {} 失败: {}", DESCRIPTOR_FILE, e))?,
+        Err(_) => ProjectDescriptor::default(),
+    };
+    // 描述文件来自不可信的压缩包：root、entry 和 mockDir 必须是包内的相对路径
+    let project_root = match &descriptor.root {
+        Some(project_root) => confined_join(root, project_root)?,
+        None => root.to_path_buf(),
+    };
+    if !confined_join(&project_root, Path::new(&descriptor.entry))?.is_file() {
+        return Err(format!("压缩包中没有入口文件 {}", descriptor.entry));
+    }
+    if let Some(mock_dir) = &descriptor.mock_dir {
+        confined_join(root, mock_dir)?;
+    }
+
+    let id = if descriptor.id.is_empty() {
+        archive_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    } else {
+        descriptor.id
+    };
+    if !is_valid_project_id(&id) {
+        return Err(format!("无效的项目 ID {:?}：只允许小写字母、数字和 \"-\"", id));
+    }
+
+    let version = descriptor
+        .version
+        .unwrap_or_else(|| chrono::Utc::now().format("%Y%m%d-%H%M%S").to_string());
+    if !is_valid_version(&version) {
+        return Err(format!("无效的版本号 {:?}：只允许字母、数字和 \".\" \"-\" \"_\"", version));
+    }
+    Ok((id, version))
+}
+
+// 替换前校验签名和全部文件
+fn verify_signature(root: &Path, integrity: &IntegrityPolicy) -> Result<(), String> {
+    match signing::verify_bundle(root, &signing::trusted_key()?)? {
+        Some(manifest) => {
+            println!("🔏 项目包签名有效: {} 个文件", manifest.files.len());
+            Ok(())
+        }
+        None if integrity.require_signature => Err("项目包没有签名，拒绝安装".to_string()),
+        None => {
+            println!("⚠️ 项目包没有签名");
+            Ok(())
+        }
+    }
+}
+
+// 用 source 替换 target：旧目录先改名备份，替换成功后删除，失败时恢复
+fn replace_dir(source: &Path, target: &Path) -> Result<(), String> {
+    let backup = target.with_file_name(format!(
+        ".old-{}-{}",
+        target.file_name().map(|name| name.to_string_lossy()).unwrap_or_default(),
+        unique_suffix()
+    ));
+    let had_previous = target.exists();
+    if had_previous {
+        std::fs::rename(target, &backup).map_err(|e| format!("备份旧目录失败 {:?}: {}", target, e))?;
+    }
+    if let Err(e) = std::fs::rename(source, target) {
+        if had_previous {
+            let _ = std::fs::rename(&backup, target);
+        }
+        return Err(format!("安装项目失败 {:?}: {}", target, e));
+    }
+    if had_previous {
+        if let Err(e) = std::fs::remove_dir_all(&backup) {
+            println!("⚠️ 删除旧目录失败 {:?}: {}", backup, e);
+        }
+    }
+    Ok(())
+}
+
+fn unique_suffix() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!("{:x}", nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use zip::write::SimpleFileOptions;
+
+    const UNSIGNED: IntegrityPolicy = IntegrityPolicy {
+        require_signature: false,
+        verify_on_serve: false,
+    };
+
+    // 写入 zip 压缩包，内容为 (路径, 内容)
+    fn write_zip(path: &Path, files: &[(&str, &str)]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in files {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn install(dir: &Path, files: &[(&str, &str)], limits: &InstallLimits) -> Result<Project, String> {
+        let archive = dir.join("studio.zip");
+        write_zip(&archive, files);
+        install_zip(&archive, &dir.join("projects"), limits, 3, &UNSIGNED)
+    }
+
+    // 项目目录下只有已安装的项目，没有残留的临时目录
+    fn installed(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir.join("projects"))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn installs_single_top_level_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            ("studio/project.json", r#"{"id": "studio", "version": "1.0.0"}"#),
+            ("studio/index.html", "<html>v1</html>"),
+        ];
+        let project = install(dir.path(), &files, &InstallLimits::default()).unwrap();
+        assert_eq!(project.id, "studio");
+        assert_eq!(project.version.as_deref(), Some("1.0.0"));
+        assert!(project.root.starts_with(dir.path().join("projects").join("studio")));
+        assert!(project.root.join("index.html").is_file());
+        assert_eq!(installed(dir.path()), ["studio"]);
+
+        // 重新安装同一版本时替换内容
+        let files = [
+            ("project.json", r#"{"id": "studio", "version": "1.0.0"}"#),
+            ("index.html", "<html>v1 fixed</html>"),
+        ];
+        let project = install(dir.path(), &files, &InstallLimits::default()).unwrap();
+        let content = std::fs::read_to_string(project.root.join("index.html")).unwrap();
+        assert_eq!(content, "<html>v1 fixed</html>");
+    }
+
+    #[test]
+    fn zip_slip_is_rejected() {
+        for name in ["../evil.html", "/evil.html", "a/../../evil.html", "a\\..\\..\\evil.html"] {
+            let dir = tempfile::tempdir().unwrap();
+            let files = [("index.html", "<html></html>"), (name, "evil")];
+            let error = install(dir.path(), &files, &InstallLimits::default()).unwrap_err();
+            assert!(error.contains("不安全的路径"), "{}: {}", name, error);
+            assert!(!dir.path().join("evil.html").exists());
+            assert!(installed(dir.path()).is_empty());
+        }
+        assert_eq!(safe_entry_path("./a/./b.html"), Some(PathBuf::from("a/b.html")));
+        assert_eq!(safe_entry_path("./"), None);
+    }
+
+    #[test]
+    fn symlink_entries_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("studio.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        writer.start_file("index.html", SimpleFileOptions::default()).unwrap();
+        writer.add_symlink("link", "/etc/passwd", SimpleFileOptions::default()).unwrap();
+        writer.finish().unwrap();
+        let projects_dir = dir.path().join("projects");
+        let error = install_zip(&archive, &projects_dir, &InstallLimits::default(), 3, &UNSIGNED).unwrap_err();
+        assert!(error.contains("符号链接"), "{}", error);
+        assert!(installed(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn size_and_entry_limits() {
+        let limits = InstallLimits {
+            max_archive_size: 64 * 1024,
+            max_entries: 3,
+            max_file_size: 16,
+            max_total_size: 24,
+        };
+        let cases: [(&[(&str, &str)], &str); 3] = [
+            (
+                &[("index.html", "<p>"), ("a.js", "a"), ("b.js", "b"), ("c.js", "c")],
+                "条目过多",
+            ),
+            (&[("index.html", "<html></html> too large")], "文件过大"),
+            (&[("index.html", "<html></html>"), ("a.js", "0123456789abcd")], "总大小"),
+        ];
+        for (files, expected) in cases {
+            let dir = tempfile::tempdir().unwrap();
+            let error = install(dir.path(), files, &limits).unwrap_err();
+            assert!(error.contains(expected), "{}: {}", expected, error);
+            assert!(installed(dir.path()).is_empty());
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let limits = InstallLimits {
+            max_archive_size: 16,
+            ..InstallLimits::default()
+        };
+        let error = install(dir.path(), &[("index.html", "<html></html>")], &limits).unwrap_err();
+        assert!(error.contains("压缩包过大"), "{}", error);
+    }
+
+    #[test]
+    fn bad_descriptor_is_rejected() {
+        let descriptors = [
+            r#"{"root": "/"}"#,
+            r#"{"root": "../"}"#,
+            r#"{"root": "dist/../.."}"#,
+            r#"{"entry": "../index.html"}"#,
+            r#"{"entry": "/etc/hostname"}"#,
+            r#"{"mockDir": "/etc"}"#,
+            r#"{"mockDir": "../x"}"#,
+            r#"{"mockDir": "mocks/../../x"}"#,
+            r#"{"id": "Bad_ID"}"#,
+            r#"{"version": "../1"}"#,
+            r#"{"entry": "missing.html"}"#,
+            "{ not json",
+        ];
+        for (index, descriptor) in descriptors.into_iter().enumerate() {
+            let dir = tempfile::tempdir().unwrap();
+            let files = [("project.json", descriptor), ("index.html", "<html></html>")];
+            let error = install(dir.path(), &files, &InstallLimits::default()).unwrap_err();
+            // 前 8 个是越出包目录的路径
+            if index < 8 {
+                assert!(error.contains("相对路径"), "{}: {}", descriptor, error);
+            }
+            assert!(installed(dir.path()).is_empty(), "{}", descriptor);
+        }
+    }
+
+    #[test]
+    fn unsigned_bundle_rejected_when_required() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("studio.zip");
+        write_zip(&archive, &[("index.html", "<html></html>")]);
+        let policy = IntegrityPolicy {
+            require_signature: true,
+            verify_on_serve: true,
+        };
+        let error = install_zip(&archive, &dir.path().join("projects"), &InstallLimits::default(), 3, &policy)
+            .unwrap_err();
+        assert!(error.contains("没有签名"), "{}", error);
+        assert!(installed(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn failed_rename_restores_previous_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("1.0.0");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::write(target.join("index.html"), "old").unwrap();
+
+        // 来源不存在，第二次 rename 失败，旧目录应恢复原位
+        assert!(replace_dir(&dir.path().join("missing"), &target).is_err());
+        assert_eq!(std::fs::read_to_string(target.join("index.html")).unwrap(), "old");
+        let names: Vec<_> = std::fs::read_dir(dir.path()).unwrap().filter_map(|entry| entry.ok()).collect();
+        assert_eq!(names.len(), 1);
+
+        // 成功时替换内容并删除备份
+        let source = dir.path().join(".staging");
+        std::fs::create_dir(&source).unwrap();
+        std::fs::write(source.join("index.html"), "new").unwrap();
+        replace_dir(&source, &target).unwrap();
+        assert_eq!(std::fs::read_to_string(target.join("index.html")).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
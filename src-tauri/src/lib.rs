@@ -1,7 +1,30 @@
 // 导入 Tauri 框架的 Manager trait，用于管理应用程序
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use serde::{Deserialize, Serialize};
 
+pub mod config;
+pub mod discovery;
+pub mod install;
+pub mod navigation;
+pub mod offline;
+pub mod projects;
+pub mod protocol;
+pub mod resource_root;
+pub mod signing;
+pub mod versions;
+pub mod watcher;
+
+use std::sync::Arc;
+
+use config::AppConfig;
+use discovery::ProjectCatalog;
+use navigation::{HomeNavigation, HomeNavigator};
+use projects::ProjectSummary;
+use protocol::{AssetCacheStats, HarMatch, OfflineQueue, ProjectMount, ProtocolResolver, QueuedRequest};
+use resource_root::{ResourceRoot, ResourceRootCandidates};
+use versions::ProjectVersion;
+use watcher::ProjectWatcher;
+
 // WebView 配置结构体
 #[derive(Debug, Serialize, Deserialize)]
 struct WebViewConfig {
@@ -221,328 +244,332 @@ fn greet(name: &str) -> String {
 // 定义一个获取资源目录路径的命令，返回资源目录的字符串路径
 // 这个函数用于前端获取资源目录路径，以便使用 asset:// 协议加载本地文件
 #[tauri::command]
-// 接收应用程序句柄，返回资源目录路径的字符串
-fn get_resource_dir(app_handle: tauri::AppHandle) -> Result<String, String> {
-    // 尝试获取资源目录路径
-    match app_handle.path().resource_dir() {
-        // 如果成功获取路径
-        Ok(path) => {
-            // 将路径转换为字符串，如果失败则返回错误
-            path.to_str()
-                .map(|s| s.to_string())
-                .ok_or_else(|| "无法将路径转换为字符串".to_string())
-        }
-        // 如果获取失败，返回错误信息
-        Err(e) => Err(format!("获取资源目录失败: {}", e)),
-    }
+// 返回启动时确定的资源目录（见 resource_root 模块的确定顺序）
+fn get_resource_dir(resource_root: tauri::State<'_, ResourceRoot>) -> Result<String, String> {
+    // 将路径转换为字符串，如果失败则返回错误
+    resource_root
+        .path
+        .to_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| "无法将路径转换为字符串".to_string())
+}
+
+// 资源目录及其来源、解析过程中的警告（例如指定的目录不存在）
+#[tauri::command]
+fn get_resource_root(resource_root: tauri::State<'_, ResourceRoot>) -> ResourceRoot {
+    resource_root.inner().clone()
 }
 
 // 简单的命令 - 前端会使用 iframe，这里不需要复杂的窗口管理
 // 保留这个命令以防未来需要
 #[tauri::command]
 fn get_project_url(project_id: String) -> String {
-    format!("myapp://{}/", project_id)
+    format!("{}://{}/", protocol::SCHEME, project_id)
 }
 
-// 这是一个条件编译属性，如果是移动平台，则使用移动端入口点
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-// 定义 Tauri 应用程序的主运行函数，这是程序的入口点
-pub fn run() {
-    // 使用默认配置创建 Tauri 应用构建器
-    tauri::Builder::default()
-        // 初始化 opener 插件，用于打开外部链接
-        .plugin(tauri_plugin_opener::init())
-        // 注册自定义协议 "myapp"
-        .register_uri_scheme_protocol("myapp", |app_handle, request| {
-            // 获取请求的 URL
-            let uri = request.uri().to_string();
-            println!("🔗 收到自定义协议请求: {}", uri);
-
-            // 解析 URL，移除 "myapp://" 前缀
-            let path = uri.strip_prefix("myapp://").unwrap_or(&uri);
-            println!("📂 解析后的路径: {}", path);
-
-            // 去掉查询参数和 hash
-            let path = path.split('?').next().unwrap_or(path);
-            let path = path.split('#').next().unwrap_or(path);
-
-            // 处理路径开头的 "./" 或 "/"
-            let path = path.trim_start_matches("./").trim_start_matches('/');
-
-            // URL 解码路径（处理 %20 等编码字符）
-            let path = match urlencoding::decode(path) {
-                Ok(decoded) => decoded.to_string(),
-                Err(_) => path.to_string(),
-            };
+// 返回首页：主窗口导航回外壳前端，首页加载完成后发送 navigated-home 事件
+#[tauri::command]
+fn navigate_home(
+    app_handle: tauri::AppHandle,
+    navigator: tauri::State<'_, HomeNavigator>,
+) -> Result<HomeNavigation, String> {
+    navigator.navigate_home(&app_handle)
+}
 
-            // 检查是否是 API 请求（如 /session/xxx, /ai/xxx 等）
-            // 这些请求应该返回 404 或空响应，因为它们是后端 API 调用
-            if path.contains("/session/") || path.contains("/ai/") || path.contains("/api/") {
-                println!("⚠️ API 请求，返回 404: {}", path);
-                return tauri::http::Response::builder()
-                    .status(404)
-                    .header("Content-Type", "application/json")
-                    .body(r#"{"error":"API endpoint not available in desktop app"}"#.as_bytes().to_vec())
-                    .unwrap();
-            }
+// 最近一次返回首页的来源，外壳挂载后用它恢复状态
+#[tauri::command]
+fn last_home_navigation(navigator: tauri::State<'_, HomeNavigator>) -> Option<HomeNavigation> {
+    navigator.last()
+}
 
-            // 如果路径为空或以 "/" 结尾，默认加载 index.html
-            let path = if path.is_empty() || path.ends_with('/') {
-                format!("{}index.html", path)
-            } else {
-                path.to_string()
-            };
+// 列出所有已注册的项目
+#[tauri::command]
+fn list_projects(catalog: tauri::State<'_, ProjectCatalog>) -> Vec<ProjectSummary> {
+    catalog.summaries()
+}
 
-            println!("🎯 最终路径: {}", path);
+// 重新扫描项目目录，协议立即按新的项目列表提供服务，并发送 projects-changed 事件
+#[tauri::command]
+fn rescan_projects(
+    app_handle: tauri::AppHandle,
+    catalog: tauri::State<'_, ProjectCatalog>,
+    resolver: tauri::State<'_, ProtocolResolver>,
+) -> Result<Vec<ProjectSummary>, String> {
+    let projects = catalog.rescan(&resolver);
+    emit_projects_changed(&app_handle, &catalog)?;
+    Ok(projects)
+}
 
-            // 获取资源目录路径
-            let resource_dir = if cfg!(debug_assertions) {
-                // 开发模式：使用项目目录下的 resources
-                std::env::current_dir()
-                    .unwrap()
-                    .join("resources")
-            } else {
-                // 生产模式：使用打包后的资源目录
-                app_handle.app_handle().path().resource_dir()
-                    .expect("无法获取资源目录")
-            };
+// 从 zip 压缩包安装项目到应用数据目录，安装后立即可以通过 myapp://{id}/ 访问
+// 解压较慢，放到后台线程执行
+#[tauri::command]
+async fn install_project(app_handle: tauri::AppHandle, zip_path: String) -> Result<ProjectSummary, String> {
+    let handle = app_handle.clone();
+    let project = tauri::async_runtime::spawn_blocking(move || {
+        let catalog = handle.state::<ProjectCatalog>();
+        let resolver = handle.state::<ProtocolResolver>();
+        catalog.install(
+            std::path::Path::new(&zip_path),
+            &install::InstallLimits::default(),
+            &resolver,
+        )
+    })
+    .await
+    .map_err(|e| format!("安装任务失败: {}", e))??;
 
-            // 拼接完整的文件路径
-            let file_path = resource_dir.join(&path);
-            println!("📁 完整文件路径: {:?}", file_path);
-
-            // 检查文件是否存在
-            if !file_path.exists() {
-                println!("❌ 文件不存在: {:?}", file_path);
-                return tauri::http::Response::builder()
-                    .status(404)
-                    .body(format!("文件不存在: {}", path).into_bytes())
-                    .unwrap();
-            }
+    emit_projects_changed(&app_handle, &app_handle.state::<ProjectCatalog>())?;
+    Ok(project)
+}
 
-            // 读取文件内容
-            match std::fs::read(&file_path) {
-                Ok(mut content) => {
-                    println!("✅ 文件读取成功，大小: {} bytes", content.len());
-
-                    // 根据文件扩展名设置 Content-Type
-                    let mime_type = match file_path.extension().and_then(|s| s.to_str()) {
-                        Some("html") => {
-                            // 为所有 HTML 注入返回首页按钮
-                            if let Ok(html_content) = String::from_utf8(content.clone()) {
-                                let inject_script = r#"
-<style>
-/* 返回首页按钮容器 */
-#tauri-back-home-container {
-  position: fixed !important;
-  top: 0 !important;
-  left: 0 !important;
-  width: 100vw !important;
-  height: 100vh !important;
-  z-index: 2147483647 !important;
-  pointer-events: none !important;
-  font-family: system-ui, -apple-system, sans-serif !important;
+// 列出已安装项目的所有版本
+#[tauri::command]
+fn list_project_versions(
+    catalog: tauri::State<'_, ProjectCatalog>,
+    project_id: String,
+) -> Result<Vec<ProjectVersion>, String> {
+    catalog.list_versions(&project_id)
 }
 
-#tauri-back-home-btn {
-  position: absolute !important;
-  top: 16px !important;
-  left: 16px !important;
-  z-index: 2147483647 !important;
-  padding: 12px 18px !important;
-  border-radius: 25px !important;
-  border: 2px solid rgba(255,255,255,0.3) !important;
-  background: linear-gradient(135deg, #667eea 0%, #764ba2 100%) !important;
-  color: #fff !important;
-  font-weight: 700 !important;
-  box-shadow: 0 6px 20px rgba(102, 126, 234, 0.4) !important;
-  cursor: pointer !important;
-  font-size: 15px !important;
-  font-family: system-ui, -apple-system, sans-serif !important;
-  pointer-events: auto !important;
-  opacity: 0.95 !important;
-  transform: none !important;
-  transition: all 0.3s ease !important;
-  backdrop-filter: blur(10px) !important;
-  user-select: none !important;
-  display: flex !important;
-  align-items: center !important;
-  gap: 6px !important;
+// 切换项目版本，协议立即从新版本目录提供服务，无需重启
+#[tauri::command]
+fn activate_project_version(
+    app_handle: tauri::AppHandle,
+    catalog: tauri::State<'_, ProjectCatalog>,
+    resolver: tauri::State<'_, ProtocolResolver>,
+    project_id: String,
+    version: String,
+) -> Result<ProjectSummary, String> {
+    let project = catalog.activate_version(&project_id, &version, &resolver)?;
+    emit_projects_changed(&app_handle, &catalog)?;
+    Ok(project)
 }
 
-#tauri-back-home-btn:hover {
-  opacity: 1 !important;
-  transform: translateY(-2px) scale(1.05) !important;
-  box-shadow: 0 8px 25px rgba(102, 126, 234, 0.6) !important;
+// 回滚到上一个激活过的版本
+#[tauri::command]
+fn rollback_project(
+    app_handle: tauri::AppHandle,
+    catalog: tauri::State<'_, ProjectCatalog>,
+    resolver: tauri::State<'_, ProtocolResolver>,
+    project_id: String,
+) -> Result<ProjectSummary, String> {
+    let project = catalog.rollback(&project_id, &resolver)?;
+    emit_projects_changed(&app_handle, &catalog)?;
+    Ok(project)
 }
 
-#tauri-back-home-btn:active {
-  transform: translateY(0px) scale(0.98) !important;
+// 项目列表变化后：开发模式下按新的项目目录重新监听，并通知前端
+fn emit_projects_changed(app_handle: &tauri::AppHandle, catalog: &ProjectCatalog) -> Result<(), String> {
+    if let Some(watcher) = app_handle.try_state::<ProjectWatcher>() {
+        watcher.watch(catalog.registry().projects());
+    }
+    app_handle
+        .emit(discovery::CHANGED_EVENT, catalog.summaries())
+        .map_err(|e| format!("发送事件失败: {}", e))
 }
 
-/* 键盘提示 */
-#tauri-keyboard-hint {
-  position: absolute !important;
-  top: 16px !important;
-  right: 16px !important;
-  background: rgba(0,0,0,0.7) !important;
-  color: #fff !important;
-  padding: 8px 12px !important;
-  border-radius: 6px !important;
-  font-size: 12px !important;
-  pointer-events: none !important;
-  opacity: 0.8 !important;
-  backdrop-filter: blur(10px) !important;
+// 协议内存资源缓存的命中率等统计
+#[tauri::command]
+fn asset_cache_stats(resolver: tauri::State<'_, ProtocolResolver>) -> AssetCacheStats {
+    resolver.asset_cache().stats()
 }
-</style>
-<script>
-(function(){
-  const containerId = 'tauri-back-home-container';
-  const btnId = 'tauri-back-home-btn';
-  const hintId = 'tauri-keyboard-hint';
-
-  // 回首页的候选 URL：优先 app://localhost（打包）、其次 dev 端口
-  const homeTargets = [
-    'app://localhost/',
-    'app://localhost/index.html',
-    'http://localhost:1420/',
-    'http://localhost:1420/index.html'
-  ];
-
-  function goHome() {
-    console.log('🏠 返回首页: candidates ->', homeTargets, 'history.length=', window.history?.length);
-
-    // 1) 优先尝试历史回退，回到原始按钮页（如果存在）
-    try {
-      if (window.history && window.history.length > 1) {
-        window.history.back();
-        setTimeout(() => window.history.back(), 50); // 再尝试一次兜底
-        return;
-      }
-    } catch (e) {
-      console.warn('history.back 失败，尝试直接跳转', e);
-    }
 
-    // 2) 直接跳转候选首页
-    for (const url of homeTargets) {
-      try {
-        window.location.href = url;
-        setTimeout(() => { window.location.replace(url); }, 50);
-        return;
-      } catch (e) {
-        console.warn('跳转失败，尝试下一个', url, e);
-      }
-    }
-  }
-
-  function createBackButton() {
-    // 清理旧容器
-    const old = document.getElementById(containerId);
-    if (old) old.remove();
-
-    const container = document.createElement('div');
-    container.id = containerId;
-
-    const btn = document.createElement('button');
-    btn.id = btnId;
-    btn.innerHTML = '🏠 返回首页';
-
-    const hint = document.createElement('div');
-    hint.id = hintId;
-    hint.innerHTML = 'Alt+H 返回首页';
-
-    btn.addEventListener('click', (e) => {
-      e.preventDefault();
-      e.stopPropagation();
-      goHome();
-    }, true);
-
-    btn.addEventListener('mousedown', (e) => {
-      e.preventDefault();
-      e.stopPropagation();
-    }, true);
-
-    container.appendChild(btn);
-    container.appendChild(hint);
-    (document.body || document.documentElement).appendChild(container);
-    console.log('✅ 返回首页按钮已创建');
-  }
-
-  function setupKeyboardShortcut() {
-    document.addEventListener('keydown', function(e) {
-      if ((e.altKey && e.key.toLowerCase() === 'h') || e.key === 'Escape') {
-        e.preventDefault();
-        e.stopPropagation();
-        goHome();
-      }
-    }, true);
-  }
-
-  function init() {
-    createBackButton();
-    setupKeyboardShortcut();
-  }
-
-  if (document.readyState === 'loading') {
-    document.addEventListener('DOMContentLoaded', init);
-  } else {
-    init();
-  }
-})();
-</script>
-"#;
-
-                                let modified_html = html_content.replace(
-                                    "</head>",
-                                    &format!("{}\n</head>", inject_script)
-                                );
-                                content = modified_html.into_bytes();
-                            }
-                            "text/html"
-                        },
-                        Some("css") => "text/css",
-                        Some("js") => "application/javascript",
-                        Some("json") => "application/json",
-                        Some("png") => "image/png",
-                        Some("jpg") | Some("jpeg") => "image/jpeg",
-                        Some("svg") => "image/svg+xml",
-                        Some("woff") | Some("woff2") => "font/woff2",
-                        Some("ttf") => "font/ttf",
-                        _ => "application/octet-stream",
-                    };
-
-                    println!("📝 Content-Type: {}", mime_type);
-
-                    tauri::http::Response::builder()
-                        .status(200)
-                        .header("Content-Type", mime_type)
-                        .body(content)
-                        .unwrap()
-                }
-                Err(e) => {
-                    println!("❌ 文件读取失败: {}", e);
-                    tauri::http::Response::builder()
-                        .status(500)
-                        .body(format!("读取文件失败: {}", e).into_bytes())
-                        .unwrap()
+// 调整资源缓存上限（字节，0 表示不缓存），立即生效，返回调整后的统计
+#[tauri::command]
+fn set_asset_cache_size(resolver: tauri::State<'_, ProtocolResolver>, size: u64) -> AssetCacheStats {
+    resolver.asset_cache().set_capacity(size);
+    resolver.asset_cache().stats()
+}
+
+// 获取已挂载项目，不存在时返回错误
+fn project_mount(resolver: &ProtocolResolver, project_id: &str) -> Result<Arc<ProjectMount>, String> {
+    resolver
+        .project(project_id)
+        .ok_or_else(|| format!("未知项目: {}", project_id))
+}
+
+// 项目的 HAR 文件：应用数据目录下的 har/{project_id}/{name}，name 只能是文件名
+fn har_file(app_handle: &tauri::AppHandle, project_id: &str, name: &str) -> Result<std::path::PathBuf, String> {
+    let har_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("获取应用数据目录失败: {}", e))?
+        .join("har");
+    protocol::har::har_path(&har_dir, project_id, name)
+}
+
+// 开始录制项目的 API 流量，返回 HAR 文件路径
+// 文件位于应用数据目录下的 har/{project_id}/，未指定文件名时以时间戳命名
+#[tauri::command]
+fn start_har_recording(
+    app_handle: tauri::AppHandle,
+    resolver: tauri::State<'_, ProtocolResolver>,
+    project_id: String,
+    name: Option<String>,
+) -> Result<String, String> {
+    let mount = project_mount(&resolver, &project_id)?;
+    let name = name.unwrap_or_else(|| chrono::Utc::now().format("%Y%m%d-%H%M%S").to_string());
+    let path = har_file(&app_handle, &project_id, &name)?;
+    mount.har().start_recording(path.clone())?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+// 停止录制，返回写入的 HAR 文件路径；没有在录制时返回 null
+#[tauri::command]
+fn stop_har_recording(
+    resolver: tauri::State<'_, ProtocolResolver>,
+    project_id: String,
+) -> Result<Option<String>, String> {
+    let path = project_mount(&resolver, &project_id)?.har().stop_recording()?;
+    Ok(path.map(|path| path.to_string_lossy().into_owned()))
+}
+
+// 选择回放使用的 HAR 文件（har/{project_id}/ 下的文件名），返回记录条数；name 为空时关闭回放
+#[tauri::command]
+fn set_active_har(
+    app_handle: tauri::AppHandle,
+    resolver: tauri::State<'_, ProtocolResolver>,
+    project_id: String,
+    name: Option<String>,
+    matching: Option<HarMatch>,
+) -> Result<usize, String> {
+    let mount = project_mount(&resolver, &project_id)?;
+    let path = match name {
+        Some(name) => Some(har_file(&app_handle, &project_id, &name)?),
+        None => None,
+    };
+    mount.har().set_replay(path, matching.unwrap_or_default())
+}
+
+// 列出离线队列中等待重试的写请求
+#[tauri::command]
+fn list_offline_queue(queue: tauri::State<'_, Arc<OfflineQueue>>) -> Vec<QueuedRequest> {
+    queue.list()
+}
+
+// 立即重试离线请求，id 为空时重试全部，返回受影响的数量
+#[tauri::command]
+fn retry_offline_queue(
+    queue: tauri::State<'_, Arc<OfflineQueue>>,
+    id: Option<String>,
+) -> Result<usize, String> {
+    queue.retry_now(id.as_deref())
+}
+
+// 丢弃离线队列中的请求
+#[tauri::command]
+fn drop_offline_request(
+    app_handle: tauri::AppHandle,
+    queue: tauri::State<'_, Arc<OfflineQueue>>,
+    id: String,
+) -> Result<(), String> {
+    queue
+        .remove(&id)?
+        .ok_or_else(|| format!("离线请求不存在: {}", id))?;
+    offline::notify_changed(&app_handle, &queue);
+    Ok(())
+}
+
+// 这是一个条件编译属性，如果是移动平台，则使用移动端入口点
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+// 定义 Tauri 应用程序的主运行函数，这是程序的入口点
+pub fn run() {
+    // 使用默认配置创建 Tauri 应用构建器
+    tauri::Builder::default()
+        // 初始化 opener 插件，用于打开外部链接
+        .plugin(tauri_plugin_opener::init())
+        // 注册自定义协议 "myapp"
+        // 请求在后台线程中处理，避免文件读取和 API 代理阻塞主线程
+        .register_asynchronous_uri_scheme_protocol(protocol::SCHEME, |ctx, request, responder| {
+            let app_handle = ctx.app_handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                // 交给协议解析器处理（解析器在 setup 中根据项目注册表创建）
+                let response = match app_handle.try_state::<ProtocolResolver>() {
+                    Some(resolver) => resolver.resolve(&request),
+                    None => tauri::http::Response::builder()
+                        .status(503)
+                        .body("协议尚未初始化".as_bytes().to_vec())
+                        .unwrap(),
+                };
+                // 写请求进入了离线队列，通知前端
+                if response.headers().contains_key("X-Offline-Queued") {
+                    if let Some(queue) = app_handle.try_state::<Arc<OfflineQueue>>() {
+                        offline::notify_changed(&app_handle, &queue);
+                    }
                 }
-            }
+                responder.respond(response);
+            });
         })
+        // 主窗口加载完首页后通知外壳
+        .on_page_load(|webview, payload| navigation::handle_page_load(webview, payload))
         // 设置应用程序初始化逻辑
         .setup(|app| {
             // 获取应用程序句柄，用于后续操作
             let app_handle = app.handle().clone();
 
-            // 在开发模式下打印资源目录路径，方便调试
+            // 确定资源目录：命令行参数 > 环境变量 > 配置文件 > 开发源码目录 > 打包后的资源目录
+            let config_file = app_handle
+                .path()
+                .app_config_dir()
+                .ok()
+                .map(|dir| dir.join(config::CONFIG_FILE));
+            // 配置文件无效时使用默认配置，错误作为资源目录的警告输出并返回给前端
+            let (app_config, config_error) = match config_file.as_deref().map(AppConfig::load) {
+                Some(Ok(config)) => (config.unwrap_or_default(), None),
+                Some(Err(e)) => (AppConfig::default(), Some(format!("{}，使用默认配置", e))),
+                None => (AppConfig::default(), None),
+            };
+            let bundled = app_handle.path().resource_dir().map_err(|e| e.to_string());
+            let mut resource_root =
+                ResourceRootCandidates::from_process(app_config.resource_dir.clone(), bundled).resolve()?;
+            if let Some(e) = config_error {
+                resource_root.warnings.insert(0, e);
+            }
+            for warning in &resource_root.warnings {
+                println!("⚠️ {}", warning);
+            }
+            println!("📦 资源目录（{:?}）: {:?}", resource_root.source, resource_root.path);
+            // 资源目录不在打包目录下时，允许 asset:// 协议访问
+            if let Err(e) = app.asset_protocol_scope().allow_directory(&resource_root.path, true) {
+                println!("⚠️ 无法允许 asset:// 访问资源目录: {}", e);
+            }
+            let resource_dir = resource_root.path.clone();
+            app.manage(resource_root);
+
+            // 注册项目清单中的项目，并扫描资源目录和用户项目目录自动发现其余项目
+            // 每个项目以 myapp://{project_id}/ 访问自己的根目录
+            let user_projects_dir = app_handle.path().app_data_dir()?.join("projects");
+            let catalog = ProjectCatalog::new(resource_dir, Some(user_projects_dir));
+            for project in catalog.registry().projects() {
+                println!("📁 注册项目: {} -> {:?}", project.id, project.root);
+            }
+            // 离线写队列保存在应用数据目录，启动后台重试线程
+            let queue_path = app_handle.path().app_data_dir()?.join("offline-queue.json");
+            let queue = Arc::new(OfflineQueue::open(queue_path)?);
+            offline::spawn_retry_worker(app_handle.clone(), queue.clone());
+
+            let resolver = catalog.registry().resolver().offline_queue(queue.clone());
+            match app_config.asset_cache_capacity() {
+                Ok(Some(size)) => resolver.asset_cache().set_capacity(size),
+                Ok(None) => {}
+                Err(e) => println!("⚠️ {}，使用默认上限", e),
+            }
+            println!("🧠 资源缓存上限: {} bytes", resolver.asset_cache().capacity());
+            app.manage(resolver);
+            app.manage(catalog);
+            // 定时把录制中的 HAR 记录写入文件
+            let flush_handle = app_handle.clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(protocol::har::FLUSH_INTERVAL);
+                flush_handle.state::<ProtocolResolver>().flush_har();
+            });
+            app.manage(queue);
+            app.manage(HomeNavigator::from_main_window(&app_handle)?);
+
+            // 开发模式：监听项目文件，变化后刷新正在显示该项目的页面
             if cfg!(debug_assertions) {
-                let dev_resource_dir = std::env::current_dir()
-                    .unwrap()
-                    .join("resources");
-                println!("🔧 开发模式资源目录: {:?}", dev_resource_dir);
-            } else {
-                if let Ok(resource_dir) = app_handle.path().resource_dir() {
-                    println!("📦 生产模式资源目录: {:?}", resource_dir);
-                }
+                let watcher = ProjectWatcher::new(app_handle.clone());
+                watcher.watch(app.state::<ProjectCatalog>().registry().projects());
+                app.manage(watcher);
             }
 
             Ok(())
@@ -553,15 +580,35 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             greet,
             get_resource_dir,
+            get_resource_root,
             get_project_url,
+            list_projects,
+            rescan_projects,
+            install_project,
+            list_project_versions,
+            activate_project_version,
+            rollback_project,
             create_child_webview,
             show_child_webview,
             hide_child_webview,
             sync_child_webviews_position,
-            close_project_window  // 保留用于清理
+            close_project_window,  // 保留用于清理
+            start_har_recording,
+            stop_har_recording,
+            set_active_har,
+            list_offline_queue,
+            retry_offline_queue,
+            drop_offline_request,
+            asset_cache_stats,
+            set_asset_cache_size,
+            navigate_home,
+            last_home_navigation
         ])
         // 运行 Tauri 应用程序，使用自动生成的上下文
         .run(tauri::generate_context!())
-        // 如果运行失败，输出错误信息并终止程序
-        .expect("error while running tauri application");
+        // 如果运行失败（包括初始化失败），输出错误信息并退出
+        .unwrap_or_else(|e| {
+            eprintln!("❌ 应用运行失败: {}", e);
+            std::process::exit(1);
+        });
 }
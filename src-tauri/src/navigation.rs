@@ -0,0 +1,185 @@
+// 返回首页：把主窗口导航回外壳前端的真实地址，页面加载完成后通知外壳恢复状态
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::http::Uri;
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url, Webview};
+
+use crate::protocol;
+
+// 主窗口标签（tauri.conf.json 中配置）
+pub const MAIN_WINDOW: &str = "main";
+
+// 从项目返回首页后，首页加载完成时发送
+pub const HOME_EVENT: &str = "navigated-home";
+
+// 返回首页事件的载荷
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeNavigation {
+    // 返回前所在的项目
+    pub from_project: Option<String>,
+    // 返回前的完整地址
+    pub from_url: Option<String>,
+}
+
+pub struct HomeNavigator {
+    // 外壳前端地址：开发模式为 devUrl，打包后为内置前端地址
+    home_url: Url,
+    // 最近一次返回首页，供外壳挂载后查询
+    last: Mutex<Option<HomeNavigation>>,
+    // 已导航、尚未发送事件
+    pending: AtomicBool,
+}
+
+impl HomeNavigator {
+    // 在 setup 中调用：主窗口此时还停留在外壳前端，直接记录它的地址，不再猜测
+    pub fn from_main_window<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Self, String> {
+        let window = app_handle
+            .get_webview_window(MAIN_WINDOW)
+            .ok_or("主窗口不存在")?;
+        let home_url = window.url().map_err(|e| format!("获取主窗口地址失败: {}", e))?;
+        Ok(Self {
+            home_url,
+            last: Mutex::new(None),
+            pending: AtomicBool::new(false),
+        })
+    }
+
+    pub fn home_url(&self) -> &Url {
+        &self.home_url
+    }
+
+    pub fn last(&self) -> Option<HomeNavigation> {
+        self.last.lock().unwrap().clone()
+    }
+
+    // 导航主窗口回首页，事件在首页加载完成后发送（见 handle_page_load）
+    pub fn navigate_home<R: Runtime>(&self, app_handle: &AppHandle<R>) -> Result<HomeNavigation, String> {
+        let window = app_handle
+            .get_webview_window(MAIN_WINDOW)
+            .ok_or("主窗口不存在")?;
+
+        let from_url = window.url().ok();
+        let from_project = from_url.as_ref().and_then(project_of);
+        let navigation = HomeNavigation {
+            from_project,
+            from_url: from_url.map(|url| url.to_string()),
+        };
+        println!("🏠 返回首页: {:?} -> {}", navigation.from_url, self.home_url);
+
+        *self.last.lock().unwrap() = Some(navigation.clone());
+        self.pending.store(true, Ordering::SeqCst);
+        window
+            .navigate(self.home_url.clone())
+            .map_err(|e| format!("返回首页失败: {}", e))?;
+        Ok(navigation)
+    }
+}
+
+// 页面地址所属的项目，不是项目页面时为 None
+pub fn project_of(url: &Url) -> Option<String> {
+    let uri = url.as_str().parse::<Uri>().ok()?;
+    if uri.scheme_str() != Some(protocol::SCHEME) && !is_windows_project_host(&uri) {
+        return None;
+    }
+    protocol::project_id(&uri).map(str::to_string)
+}
+
+// Windows 上项目地址形如 http://myapp.{project_id}/
+fn is_windows_project_host(uri: &Uri) -> bool {
+    uri.host()
+        .is_some_and(|host| host.starts_with(&format!("{}.", protocol::SCHEME)))
+}
+
+// 页面地址是否是外壳首页：只比较协议、主机和端口，路径末尾的 "/"、查询参数和锚点（前端路由）不影响判断
+// tauri:// 等非特殊协议的 Url::origin() 是不透明的，互不相等，因此逐项比较
+pub fn is_home_url(url: &Url, home_url: &Url) -> bool {
+    url.scheme() == home_url.scheme()
+        && url.host_str() == home_url.host_str()
+        && url.port_or_known_default() == home_url.port_or_known_default()
+}
+
+// 主窗口加载完首页时发送返回首页事件；页面脚本此时已执行，外壳可以可靠地收到
+pub fn handle_page_load<R: Runtime>(webview: &Webview<R>, payload: &PageLoadPayload<'_>) {
+    if webview.label() != MAIN_WINDOW || !matches!(payload.event(), PageLoadEvent::Finished) {
+        return;
+    }
+    let Some(navigator) = webview.try_state::<HomeNavigator>() else {
+        return;
+    };
+    if !is_home_url(payload.url(), navigator.home_url()) {
+        return;
+    }
+    if !navigator.pending.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(navigation) = navigator.last() {
+        if let Err(e) = webview.emit(HOME_EVENT, navigation) {
+            println!("⚠️ 发送事件失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(value: &str) -> Url {
+        Url::parse(value).unwrap()
+    }
+
+    // 首页地址为 home 时，依次判断 urls 是否是首页
+    fn check(home: &str, urls: &[(&str, bool)]) {
+        let home = url(home);
+        for (value, expected) in urls {
+            assert_eq!(is_home_url(&url(value), &home), *expected, "{} vs {}", value, home);
+        }
+    }
+
+    #[test]
+    fn dev_server_home() {
+        check(
+            "http://localhost:1420/",
+            &[
+                ("http://localhost:1420", true),
+                ("http://localhost:1420/", true),
+                ("http://localhost:1420/?from=studio", true),
+                ("http://localhost:1420/#/projects", true),
+                ("http://localhost:1420/index.html?a=1#top", true),
+                ("http://localhost:1421/", false),
+                ("https://localhost:1420/", false),
+                ("http://127.0.0.1:1420/", false),
+                ("myapp://studio/", false),
+            ],
+        );
+    }
+
+    #[test]
+    fn bundled_home() {
+        // macOS / Linux：非特殊协议没有默认端口
+        check(
+            "tauri://localhost",
+            &[
+                ("tauri://localhost", true),
+                ("tauri://localhost/", true),
+                ("tauri://localhost/?tab=1#/settings", true),
+                ("tauri://other/", false),
+                ("myapp://localhost/", false),
+                ("myapp://studio/index.html", false),
+            ],
+        );
+        // Windows：http://tauri.localhost，项目为 http://myapp.{id}
+        check(
+            "http://tauri.localhost/",
+            &[
+                ("http://tauri.localhost", true),
+                ("http://tauri.localhost:80/#/", true),
+                ("http://myapp.studio/", false),
+                ("https://tauri.localhost/", false),
+            ],
+        );
+    }
+}
@@ -0,0 +1,144 @@
+// 离线写队列的后台重试：到期的请求重新经反向代理转发，完成后通过事件通知页面
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::http::Response;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::protocol::{OfflineQueue, ProtocolResolver, QueuedRequest};
+
+// 队列中的请求最终完成（上游已响应）
+pub const COMPLETED_EVENT: &str = "offline-queue-completed";
+// 队列中的请求永久失败，不再自动重试
+pub const FAILED_EVENT: &str = "offline-queue-failed";
+// 队列内容变化（入队、完成、丢弃）
+pub const CHANGED_EVENT: &str = "offline-queue-changed";
+
+// 没有到期请求时的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletedPayload {
+    pub id: String,
+    pub project_id: String,
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedPayload {
+    pub id: String,
+    pub method: String,
+    pub url: String,
+    pub error: String,
+}
+
+// 启动重试线程
+pub fn spawn_retry_worker(app_handle: AppHandle, queue: Arc<OfflineQueue>) {
+    std::thread::spawn(move || loop {
+        for item in queue.due() {
+            retry(&app_handle, &queue, item);
+        }
+        queue.wait(POLL_INTERVAL);
+    });
+}
+
+// 重试失败的原因
+enum RetryError {
+    // 网络错误，按退避策略稍后重试
+    Network(String),
+    // 项目已卸载、没有代理规则、请求无法还原等，重试也不会成功
+    Permanent(String),
+}
+
+// 重试一个请求：上游有响应即视为完成（无论状态码）；网络错误按退避策略等待下一次，其他错误标记为永久失败
+fn retry(app_handle: &AppHandle, queue: &OfflineQueue, item: QueuedRequest) {
+    println!("🔁 重试离线请求: {} {} (第 {} 次)", item.method, item.url, item.attempts + 1);
+
+    let result = match app_handle.try_state::<ProtocolResolver>() {
+        Some(resolver) => forward(&resolver, &item),
+        // 启动过程中协议还没有初始化，稍后再试
+        None => Err(RetryError::Network("协议尚未初始化".to_string())),
+    };
+
+    match result {
+        Ok(response) => {
+            println!("✅ 离线请求已完成: {} -> {}", item.id, response.status());
+            if let Err(e) = queue.remove(&item.id) {
+                println!("⚠️ {}", e);
+            }
+            let payload = CompletedPayload {
+                status: response.status().as_u16(),
+                body: String::from_utf8_lossy(response.body()).into_owned(),
+                id: item.id,
+                method: item.method,
+                url: item.url,
+                project_id: item.project_id,
+            };
+            // 响应体只发给发出请求的项目
+            emit_to_project(app_handle, &payload.project_id, COMPLETED_EVENT, &payload);
+            notify_changed(app_handle, queue);
+        }
+        Err(RetryError::Network(e)) => {
+            println!("⚠️ 离线请求重试失败 {}: {}", item.id, e);
+            if let Err(e) = queue.mark_failed(&item.id, &e) {
+                println!("⚠️ {}", e);
+            }
+        }
+        Err(RetryError::Permanent(e)) => {
+            println!("❌ 离线请求无法完成，停止重试 {}: {}", item.id, e);
+            if let Err(e) = queue.mark_permanent_failure(&item.id, &e) {
+                println!("⚠️ {}", e);
+            }
+            let payload = FailedPayload {
+                id: item.id,
+                method: item.method,
+                url: item.url,
+                error: e,
+            };
+            emit_to_project(app_handle, &item.project_id, FAILED_EVENT, &payload);
+            notify_changed(app_handle, queue);
+        }
+    }
+}
+
+// 经项目的反向代理重新转发请求
+fn forward(resolver: &ProtocolResolver, item: &QueuedRequest) -> Result<Response<Vec<u8>>, RetryError> {
+    let mount = resolver
+        .project(&item.project_id)
+        .ok_or_else(|| RetryError::Permanent(format!("项目 {} 不存在", item.project_id)))?;
+    let proxy = mount
+        .reverse_proxy()
+        .ok_or_else(|| RetryError::Permanent(format!("项目 {} 没有配置反向代理", item.project_id)))?;
+    let request = item.to_request().map_err(RetryError::Permanent)?;
+    match proxy.forward(&request) {
+        Some(Ok(response)) => Ok(response),
+        Some(Err(e)) if e.is_retryable() => Err(RetryError::Network(e.to_string())),
+        Some(Err(e)) => Err(RetryError::Permanent(e.to_string())),
+        None => Err(RetryError::Permanent("没有匹配的代理规则".to_string())),
+    }
+}
+
+// 发给项目所在的子 WebView；项目窗口没有打开时发给主窗口
+fn emit_to_project<S: Serialize + Clone>(app_handle: &AppHandle, project_id: &str, event: &str, payload: &S) {
+    let label = format!("child_{}", project_id);
+    let opened = app_handle
+        .get_window("main")
+        .is_some_and(|window| window.get_webview(&label).is_some());
+    let target = if opened { label.as_str() } else { "main" };
+    if let Err(e) = app_handle.emit_to(target, event, payload) {
+        println!("⚠️ 发送事件失败: {}", e);
+    }
+}
+
+// 通知前端队列内容变化，载荷为当前队列长度
+pub fn notify_changed(app_handle: &AppHandle, queue: &OfflineQueue) {
+    if let Err(e) = app_handle.emit(CHANGED_EVENT, queue.list().len()) {
+        println!("⚠️ 发送事件失败: {}", e);
+    }
+}
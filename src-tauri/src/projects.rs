@@ -0,0 +1,367 @@
+// 项目注册表：项目 ID（myapp:// URL 的 host）到项目根目录的映射，从资源目录下的 projects.json 加载
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::source::normalize_path;
+use crate::protocol::{
+    archive, AssetSource, CachePolicy, CompressionConfig, EmbeddedAssetSource, FileHashes, FsAssetSource, InjectorPipeline, MockBackend, OverlayConfig,
+    ProjectMount, ProtocolResolver, ProxyRule, UnsignedAssetSource, VerifiedAssetSource, DEFAULT_DOCUMENT, SCHEME,
+};
+
+// 描述文件中的相对路径（root、mockDir 等）：只能由普通路径段组成，不能是绝对路径或包含 ".."
+// 已存在的路径解析符号链接后仍须位于 base 之内
+pub fn confined_join(base: &Path, relative: &Path) -> Result<PathBuf, String> {
+    let normalized = relative
+        .to_str()
+        .and_then(normalize_path)
+        .ok_or_else(|| format!("路径必须是目录内的相对路径: {:?}", relative))?;
+    let path = base.join(normalized);
+    if let (Ok(real_base), Ok(real_path)) = (base.canonicalize(), path.canonicalize()) {
+        if !real_path.starts_with(&real_base) {
+            return Err(format!("路径越出目录 {:?}: {:?}", base, relative));
+        }
+    }
+    Ok(path)
+}
+
+// 项目清单文件名（位于资源目录下）
+pub const MANIFEST_FILE: &str = "projects.json";
+
+// 默认图标文件
+pub const ICON_FILE: &str = "favicon.ico";
+
+// 项目窗口配置，原样交给前端
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowConfig {
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub resizable: Option<bool>,
+    pub fullscreen: Option<bool>,
+}
+
+// 开发模式下文件变化后的自动刷新
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LiveReloadConfig {
+    // 关闭后仍会发送 project-files-changed 事件，但不刷新页面
+    pub enabled: bool,
+    // 只有 CSS 变化时替换样式表，不刷新整个页面
+    pub css_hot_swap: bool,
+}
+
+impl Default for LiveReloadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            css_hot_swap: false,
+        }
+    }
+}
+
+// 项目描述：清单中的一项，或项目目录下的 project.json
+// 路径均相对于清单所在目录；project.json 中省略 id 和 root 时取所在文件夹
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProjectDescriptor {
+    pub id: String,
+    pub name: Option<String>,
+    pub description: String,
+    // 版本号，安装时作为版本目录名，例如 1.4.2
+    pub version: Option<String>,
+    // 图标（相对于项目根目录），默认为 favicon.ico（存在时）
+    pub icon: Option<String>,
+    // 项目根目录，默认为与 ID 同名的文件夹
+    pub root: Option<PathBuf>,
+    pub entry: String,
+    pub spa_fallback: bool,
+    pub window: Option<WindowConfig>,
+    pub overlay: OverlayConfig,
+    // 按名称开启 / 关闭注入器，例如 {"back-home-script": false}
+    pub injectors: HashMap<String, bool>,
+    pub proxy: Vec<ProxyRule>,
+    // 模拟后端目录，project.json 中相对于项目文件夹；默认为 mocks/{id}（存在时）
+    pub mock_dir: Option<PathBuf>,
+    pub mime_overrides: HashMap<String, String>,
+    pub cache_policy: CachePolicy,
+    pub compression: CompressionConfig,
+    pub live_reload: LiveReloadConfig,
+}
+
+impl Default for ProjectDescriptor {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: None,
+            description: String::new(),
+            version: None,
+            icon: None,
+            root: None,
+            entry: DEFAULT_DOCUMENT.to_string(),
+            spa_fallback: false,
+            window: None,
+            overlay: OverlayConfig::default(),
+            injectors: HashMap::new(),
+            proxy: Vec::new(),
+            mock_dir: None,
+            mime_overrides: HashMap::new(),
+            cache_policy: CachePolicy::default(),
+            compression: CompressionConfig::default(),
+            live_reload: LiveReloadConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    projects: Vec<ProjectDescriptor>,
+}
+
+// 项目 ID 会作为 URL 的 host，只允许小写字母、数字和 "-"
+pub fn is_valid_project_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('-')
+        && !id.ends_with('-')
+        && id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+// 单个项目
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    // 已安装项目的当前版本
+    pub version: Option<String>,
+    // 图标（相对于项目根目录）
+    pub icon: Option<String>,
+    // 项目根目录，入口文件位于该目录下
+    pub root: PathBuf,
+    // 入口文件（相对于根目录），默认为 index.html
+    pub entry: String,
+    // 找不到的前端路由是否回退到入口文件
+    pub spa_fallback: bool,
+    pub window: Option<WindowConfig>,
+    // Cache-Control 策略
+    pub cache_policy: CachePolicy,
+    // 预压缩 / 即时压缩配置
+    pub compression: CompressionConfig,
+    // 自定义扩展名的 MIME 类型
+    pub mime_overrides: HashMap<String, String>,
+    // 本地模拟后端目录（包含 routes/*.json），不存在时 API 请求返回 404
+    pub mock_dir: Option<PathBuf>,
+    // 反向代理规则，按顺序匹配
+    pub proxy_rules: Vec<ProxyRule>,
+    // HTML 注入管线，可按名称开启 / 关闭单个注入器
+    pub injectors: InjectorPipeline,
+    // 返回首页浮层配置，生成管线中的 back-home-style / back-home-script
+    pub overlay: OverlayConfig,
+    // 签名清单中的文件哈希（相对于项目根目录），签名有效的项目包才有
+    pub integrity: Option<FileHashes>,
+    // 签名清单中模拟后端文件的哈希（相对于 mock_dir），模拟后端位于签名的项目包内时才有
+    pub mock_integrity: Option<FileHashes>,
+    // 必须有有效签名才能提供服务（用户安装的项目，策略要求签名时）
+    pub require_signature: bool,
+    // 内嵌资源包中的项目目录（embedded-assets 构建），设置后不再读取 root
+    pub embedded: Option<EmbeddedAssetSource>,
+    // 开发模式文件变化后的刷新方式
+    pub live_reload: LiveReloadConfig,
+}
+
+impl Project {
+    // 按描述创建项目，root 以 base_dir 为基准；mockDir 相对于 project_dir（项目文件夹，清单项目为清单所在目录），不能越出它
+    // 没有配置 mockDir 时使用 base_dir 下的 mocks/{id}（存在时）
+    pub fn from_descriptor(entry: ProjectDescriptor, base_dir: &Path, project_dir: &Path) -> Result<Self, String> {
+        let mut injectors = InjectorPipeline::builtin();
+        for (name, enabled) in &entry.injectors {
+            if !injectors.set_enabled(name, *enabled) {
+                println!("⚠️ 项目 {} 配置了未知的注入器: {}", entry.id, name);
+            }
+        }
+        let mock_dir = match entry.mock_dir {
+            Some(dir) => Some(confined_join(project_dir, &dir)?),
+            None => Some(base_dir.join("mocks").join(&entry.id)).filter(|dir| dir.is_dir()),
+        };
+
+        let root = base_dir.join(entry.root.unwrap_or_else(|| PathBuf::from(&entry.id)));
+        let icon = entry
+            .icon
+            .or_else(|| Some(ICON_FILE.to_string()).filter(|icon| root.join(icon).is_file()));
+
+        Ok(Project {
+            name: entry.name.unwrap_or_else(|| entry.id.clone()),
+            description: entry.description,
+            version: entry.version,
+            icon,
+            root,
+            entry: entry.entry,
+            spa_fallback: entry.spa_fallback,
+            window: entry.window,
+            cache_policy: entry.cache_policy,
+            compression: entry.compression,
+            mime_overrides: entry.mime_overrides,
+            mock_dir,
+            proxy_rules: entry.proxy,
+            injectors,
+            overlay: entry.overlay,
+            integrity: None,
+            mock_integrity: None,
+            require_signature: false,
+            embedded: None,
+            live_reload: entry.live_reload,
+            id: entry.id,
+        })
+    }
+
+    // 协议挂载配置，项目根目录（或 .zip / .tar 压缩包）即资源来源；verify_files 时按签名清单校验读取的文件
+    pub fn mount(&self, verify_files: bool) -> ProjectMount {
+        let source: Box<dyn AssetSource> = match &self.embedded {
+            Some(embedded) => Box::new(embedded.clone()),
+            // 发现时已跳过没有签名的项目，这里再检查一次，避免未经签名的内容被挂载
+            None if self.require_signature && self.integrity.is_none() => {
+                println!("🚫 项目 {} 没有有效签名，拒绝提供服务", self.id);
+                Box::new(UnsignedAssetSource)
+            }
+            None => archive::open_source(&self.root).unwrap_or_else(|e| {
+                println!("⚠️ 项目 {} {}", self.id, e);
+                Box::new(FsAssetSource::new(self.root.clone()))
+            }),
+        };
+        let mount = match &self.integrity {
+            Some(hashes) if verify_files => {
+                println!("🛡️ 项目 {} 启用文件校验: {} 个文件", self.id, hashes.len());
+                ProjectMount::new(VerifiedAssetSource::new(source, hashes.clone()))
+            }
+            _ => ProjectMount::new(source),
+        };
+        let mut mount = mount
+            .entry(self.entry.clone())
+            .spa_fallback(self.spa_fallback)
+            .cache_policy(self.cache_policy.clone())
+            .compression(self.compression.clone())
+            .mime_overrides(self.mime_overrides.clone())
+            .proxy(self.proxy_rules.clone())
+            .injectors(self.injectors.clone().with_overlay(&self.overlay));
+        // 没有有效签名的项目同样不加载模拟后端
+        let unsigned = self.require_signature && self.integrity.is_none() && self.embedded.is_none();
+        if let Some(mock_dir) = self.mock_dir.as_ref().filter(|_| !unsigned) {
+            let integrity = self.mock_integrity.clone().filter(|_| verify_files);
+            match MockBackend::load_verified(mock_dir, integrity) {
+                Ok(mock) => {
+                    println!("🎭 项目 {} 加载模拟后端: {} 条路由", self.id, mock.routes().len());
+                    mount = mount.mock(mock);
+                }
+                Err(e) => println!("⚠️ 项目 {} 模拟后端加载失败: {}", self.id, e),
+            }
+        }
+        mount
+    }
+
+    // 项目访问地址
+    pub fn url(&self) -> String {
+        format!("{}://{}/", SCHEME, self.id)
+    }
+
+    pub fn summary(&self) -> ProjectSummary {
+        ProjectSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            version: self.version.clone(),
+            signed: self.integrity.is_some(),
+            url: self.url(),
+            icon: self.icon.as_ref().map(|icon| format!("{}{}", self.url(), icon.trim_start_matches('/'))),
+            entry: self.entry.clone(),
+            window: self.window.clone(),
+        }
+    }
+}
+
+// 提供给前端的项目信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSummary {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub version: Option<String>,
+    // 是否是签名有效的项目包
+    pub signed: bool,
+    pub url: String,
+    // 图标地址（myapp://{id}/favicon.ico）
+    pub icon: Option<String>,
+    pub entry: String,
+    pub window: Option<WindowConfig>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProjectRegistry {
+    projects: Vec<Project>,
+    // 挂载时是否按签名清单校验文件
+    verify_files: bool,
+}
+
+impl ProjectRegistry {
+    // 从清单文件加载项目
+    pub fn load_manifest(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read(path).map_err(|e| format!("读取项目清单失败 {:?}: {}", path, e))?;
+        Self::parse_manifest(&content, path)
+    }
+
+    // 解析清单内容，项目路径相对于 path 所在目录
+    pub fn parse_manifest(content: &[u8], path: &Path) -> Result<Self, String> {
+        let manifest: Manifest = serde_json::from_slice(content)
+            .map_err(|e| format!("解析项目清单失败 {:?}: {}", path, e))?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+
+        let mut registry = Self::default();
+        for entry in manifest.projects {
+            if !is_valid_project_id(&entry.id) {
+                return Err(format!("无效的项目 ID {:?}：只允许小写字母、数字和 \"-\"", entry.id));
+            }
+            if registry.get(&entry.id).is_some() {
+                return Err(format!("项目清单中存在重复的项目 ID: {}", entry.id));
+            }
+            registry.register(Project::from_descriptor(entry, base_dir, base_dir)?);
+        }
+        Ok(registry)
+    }
+
+    // 注册项目，同 ID 的项目原位替换，保持列表顺序
+    pub fn register(&mut self, project: Project) {
+        match self.projects.iter_mut().find(|p| p.id == project.id) {
+            Some(existing) => *existing = project,
+            None => self.projects.push(project),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Project> {
+        self.projects.iter().find(|p| p.id == id)
+    }
+
+    pub fn projects(&self) -> &[Project] {
+        &self.projects
+    }
+
+    pub fn verify_files(mut self, enabled: bool) -> Self {
+        self.verify_files = enabled;
+        self
+    }
+
+    // 根据注册表构建协议解析器，每个项目挂载自己的根目录
+    pub fn resolver(&self) -> ProtocolResolver {
+        let resolver = ProtocolResolver::new();
+        resolver.replace_projects(self.mounts());
+        resolver
+    }
+
+    // 每个项目的挂载配置
+    pub fn mounts(&self) -> HashMap<String, ProjectMount> {
+        self.projects
+            .iter()
+            .map(|project| (project.id.clone(), project.mount(self.verify_files)))
+            .collect()
+    }
+}
@@ -0,0 +1,428 @@
+// 压缩包资源来源：直接从 .zip 或未压缩的 .tar 读取项目文件，不解压
+// 打开时建立一次索引（路径 -> 位置、大小），之后按索引读取；未压缩的条目支持直接分段读取
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use zip::{CompressionMethod, ZipArchive};
+
+use super::source::{normalize_path, AssetError, AssetMetadata, AssetSource, FsAssetSource};
+
+// 支持的压缩包格式，按扩展名识别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "zip" => Some(ArchiveFormat::Zip),
+            "tar" => Some(ArchiveFormat::Tar),
+            _ => None,
+        }
+    }
+}
+
+// 按项目根目录打开资源来源：压缩包文件使用对应的压缩包来源，其他使用本地目录
+pub fn open_source(root: &Path) -> Result<Box<dyn AssetSource>, String> {
+    Ok(match ArchiveFormat::from_path(root).filter(|_| root.is_file()) {
+        Some(ArchiveFormat::Zip) => Box::new(ZipAssetSource::open(root)?),
+        Some(ArchiveFormat::Tar) => Box::new(TarAssetSource::open(root)?),
+        None => Box::new(FsAssetSource::new(root)),
+    })
+}
+
+// 所有文件都位于同一个顶层目录下时（如 studio/index.html），去掉该目录
+fn strip_top_level_dir<T>(entries: Vec<(String, T)>) -> HashMap<String, T> {
+    let top_level = entries
+        .first()
+        .and_then(|(name, _)| name.split_once('/'))
+        .map(|(dir, _)| format!("{}/", dir));
+    match top_level {
+        Some(prefix) if entries.iter().all(|(name, _)| name.starts_with(&prefix)) => entries
+            .into_iter()
+            .map(|(name, value)| (name[prefix.len()..].to_string(), value))
+            .collect(),
+        _ => entries.into_iter().collect(),
+    }
+}
+
+// 从压缩包文件的 offset 处读取 len 个字节
+fn read_at(path: &Path, offset: u64, len: u64) -> Result<Vec<u8>, AssetError> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut content = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut content)?;
+    Ok(content)
+}
+
+// 分段读取的区间，超出文件长度的部分截掉
+fn clamp_range(size: u64, start: u64, len: u64) -> (u64, u64) {
+    let start = start.min(size);
+    (start, len.min(size - start))
+}
+
+fn io_error(e: impl std::fmt::Display) -> AssetError {
+    AssetError::Io(std::io::Error::other(e.to_string()))
+}
+
+struct ZipEntry {
+    index: usize,
+    size: u64,
+    // 未压缩（Stored）条目的数据起始位置，可以直接按位置读取
+    stored_at: Option<u64>,
+}
+
+// 从 zip 文件读取资源
+pub struct ZipAssetSource {
+    path: PathBuf,
+    // 压缩包文件的修改时间，作为所有条目的修改时间，替换压缩包后 ETag 随之变化
+    modified: Option<SystemTime>,
+    entries: HashMap<String, ZipEntry>,
+    archive: Mutex<ZipArchive<File>>,
+}
+
+impl ZipAssetSource {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("打开压缩包失败 {:?}: {}", path, e))?;
+        let modified = file.metadata().and_then(|metadata| metadata.modified()).ok();
+        let mut archive = ZipArchive::new(file).map_err(|e| format!("无效的 zip 压缩包 {:?}: {}", path, e))?;
+
+        let mut entries = Vec::new();
+        for index in 0..archive.len() {
+            let entry = archive
+                .by_index_raw(index)
+                .map_err(|e| format!("读取压缩包条目失败 {:?}: {}", path, e))?;
+            // 跳过目录、符号链接、加密条目和不安全的路径
+            if entry.is_dir() || entry.is_symlink() || entry.encrypted() {
+                continue;
+            }
+            let Some(name) = normalize_path(entry.name()) else {
+                continue;
+            };
+            let stored_at = (entry.compression() == CompressionMethod::Stored).then(|| entry.data_start());
+            entries.push((
+                name,
+                ZipEntry {
+                    index,
+                    size: entry.size(),
+                    stored_at,
+                },
+            ));
+        }
+        let entries = strip_top_level_dir(entries);
+        println!("🗜️ 已索引压缩包 {:?}: {} 个文件", path, entries.len());
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            modified,
+            entries,
+            archive: Mutex::new(archive),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn entry(&self, path: &str) -> Result<&ZipEntry, AssetError> {
+        let name = normalize_path(path).ok_or(AssetError::Forbidden)?;
+        self.entries.get(&name).ok_or(AssetError::NotFound)
+    }
+}
+
+impl AssetSource for ZipAssetSource {
+    fn metadata(&self, path: &str) -> Result<AssetMetadata, AssetError> {
+        let entry = self.entry(path)?;
+        Ok(AssetMetadata {
+            len: entry.size,
+            modified: self.modified,
+        })
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let entry = self.entry(path)?;
+        if let Some(offset) = entry.stored_at {
+            return read_at(&self.path, offset, entry.size);
+        }
+        let mut archive = self.archive.lock().unwrap();
+        let mut file = archive.by_index(entry.index).map_err(io_error)?;
+        let mut content = Vec::with_capacity(entry.size as usize);
+        file.read_to_end(&mut content)?;
+        Ok(content)
+    }
+
+    // 未压缩的条目直接按位置读取，压缩的条目只能解压后截取
+    fn read_range(&self, path: &str, start: u64, len: u64) -> Result<Vec<u8>, AssetError> {
+        let entry = self.entry(path)?;
+        match entry.stored_at {
+            Some(offset) => {
+                let (start, len) = clamp_range(entry.size, start, len);
+                read_at(&self.path, offset + start, len)
+            }
+            None => {
+                let content = self.read(path)?;
+                let (start, len) = clamp_range(content.len() as u64, start, len);
+                Ok(content[start as usize..(start + len) as usize].to_vec())
+            }
+        }
+    }
+}
+
+// 从未压缩的 tar 文件读取资源，所有条目都可以直接按位置读取
+pub struct TarAssetSource {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    // 路径 -> (数据起始位置, 大小)
+    entries: HashMap<String, (u64, u64)>,
+}
+
+impl TarAssetSource {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("打开压缩包失败 {:?}: {}", path, e))?;
+        let modified = file.metadata().and_then(|metadata| metadata.modified()).ok();
+        let mut archive = tar::Archive::new(file);
+
+        let mut entries = Vec::new();
+        let invalid = |e: std::io::Error| format!("无效的 tar 文件 {:?}: {}", path, e);
+        for entry in archive.entries_with_seek().map_err(invalid)? {
+            let entry = entry.map_err(invalid)?;
+            // 只索引普通文件，跳过目录和链接
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry.path().ok().and_then(|name| normalize_path(&name.to_string_lossy()));
+            if let Some(name) = name {
+                entries.push((name, (entry.raw_file_position(), entry.size())));
+            }
+        }
+        let entries = strip_top_level_dir(entries);
+        println!("🗜️ 已索引压缩包 {:?}: {} 个文件", path, entries.len());
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            modified,
+            entries,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn entry(&self, path: &str) -> Result<(u64, u64), AssetError> {
+        let name = normalize_path(path).ok_or(AssetError::Forbidden)?;
+        self.entries.get(&name).copied().ok_or(AssetError::NotFound)
+    }
+}
+
+impl AssetSource for TarAssetSource {
+    fn metadata(&self, path: &str) -> Result<AssetMetadata, AssetError> {
+        let (_, size) = self.entry(path)?;
+        Ok(AssetMetadata {
+            len: size,
+            modified: self.modified,
+        })
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let (offset, size) = self.entry(path)?;
+        read_at(&self.path, offset, size)
+    }
+
+    fn read_range(&self, path: &str, start: u64, len: u64) -> Result<Vec<u8>, AssetError> {
+        let (offset, size) = self.entry(path)?;
+        let (start, len) = clamp_range(size, start, len);
+        read_at(&self.path, offset + start, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tauri::http::{header, Request};
+
+    use super::*;
+    use crate::protocol::{ProjectMount, ProtocolResolver};
+
+    const SCRIPT: &[u8] = b"console.log('archive');";
+
+    fn bytes() -> Vec<u8> {
+        (0..=255).collect()
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8], CompressionMethod)]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content, method) in files {
+            let options = zip::write::SimpleFileOptions::default().compression_method(*method);
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    // 名称直接写入头部，绕过 tar 对 ".." 的检查，模拟恶意压缩包
+    fn write_tar(path: &Path, files: &[(&str, &[u8])]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for (name, content) in files {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_cksum();
+            builder.append(&header, *content).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    fn sources(dir: &Path, files: &[(&str, &[u8])]) -> Vec<Box<dyn AssetSource>> {
+        let zip_files: Vec<_> = files
+            .iter()
+            .map(|(name, content)| (*name, *content, CompressionMethod::Deflated))
+            .collect();
+        write_zip(&dir.join("project.zip"), &zip_files);
+        write_tar(&dir.join("project.tar"), files);
+        vec![
+            open_source(&dir.join("project.zip")).unwrap(),
+            open_source(&dir.join("project.tar")).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn serves_files_from_zip_and_tar() {
+        let dir = tempfile::tempdir().unwrap();
+        let files: [(&str, &[u8]); 2] = [("studio/index.html", b"<html></html>"), ("studio/assets/app.js", SCRIPT)];
+        for source in sources(dir.path(), &files) {
+            assert_eq!(source.read("index.html").unwrap(), b"<html></html>");
+            assert_eq!(source.read("assets/./app.js").unwrap(), SCRIPT);
+            assert_eq!(source.metadata("assets/app.js").unwrap().len, SCRIPT.len() as u64);
+            assert!(source.metadata("index.html").unwrap().modified.is_some());
+            assert!(matches!(source.read("missing.js"), Err(AssetError::NotFound)));
+            assert!(matches!(source.read("../project.zip"), Err(AssetError::Forbidden)));
+        }
+        assert!(ZipAssetSource::open(&dir.path().join("project.tar")).is_err());
+    }
+
+    #[test]
+    fn stored_entries_support_range_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = bytes();
+        let zip_path = dir.path().join("media.zip");
+        write_zip(
+            &zip_path,
+            &[
+                ("stored.bin", &content, CompressionMethod::Stored),
+                ("deflated.bin", &content, CompressionMethod::Deflated),
+            ],
+        );
+        let zip = ZipAssetSource::open(&zip_path).unwrap();
+        assert!(zip.entries["stored.bin"].stored_at.is_some());
+        assert!(zip.entries["deflated.bin"].stored_at.is_none());
+
+        let tar_path = dir.path().join("media.tar");
+        write_tar(&tar_path, &[("stored.bin", &content)]);
+        let tar = TarAssetSource::open(&tar_path).unwrap();
+
+        let sources: [(&dyn AssetSource, &str); 3] = [(&zip, "stored.bin"), (&zip, "deflated.bin"), (&tar, "stored.bin")];
+        for (source, name) in sources {
+            assert_eq!(source.read(name).unwrap(), content, "{}", name);
+            assert_eq!(source.read_range(name, 10, 5).unwrap(), &content[10..15], "{}", name);
+            // 超出文件长度的部分截掉
+            assert_eq!(source.read_range(name, 250, 100).unwrap(), &content[250..], "{}", name);
+            assert!(source.read_range(name, 300, 10).unwrap().is_empty(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn strips_single_top_level_dir_only() {
+        let stripped = strip_top_level_dir(vec![("app/index.html".to_string(), 1), ("app/js/a.js".to_string(), 2)]);
+        assert_eq!(stripped.get("index.html"), Some(&1));
+        assert_eq!(stripped.get("js/a.js"), Some(&2));
+
+        // 有文件不在同一目录下时保持原样
+        for entries in [
+            vec![("app/index.html".to_string(), 1), ("other/a.js".to_string(), 2)],
+            vec![("app/index.html".to_string(), 1), ("index.html".to_string(), 2)],
+            vec![("app/index.html".to_string(), 1), ("apple/a.js".to_string(), 2)],
+            vec![("index.html".to_string(), 1)],
+        ] {
+            let names: Vec<String> = entries.iter().map(|(name, _)| name.clone()).collect();
+            let result = strip_top_level_dir(entries);
+            assert!(names.iter().all(|name| result.contains_key(name)), "{:?}", names);
+        }
+    }
+
+    #[test]
+    fn dot_dot_entries_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let files: [(&str, &[u8]); 4] = [
+            ("index.html", b"<html></html>"),
+            ("../evil.txt", b"evil"),
+            ("assets/../../evil.js", b"evil"),
+            ("/etc/passwd", b"evil"),
+        ];
+        for source in sources(dir.path(), &files) {
+            assert!(source.read("index.html").is_ok());
+            for name in ["evil.txt", "../evil.txt", "evil.js", "etc/passwd", "/etc/passwd"] {
+                assert!(source.read(name).is_err(), "{}", name);
+            }
+        }
+        let zip = ZipAssetSource::open(&dir.path().join("project.zip")).unwrap();
+        assert_eq!(zip.entries.keys().collect::<Vec<_>>(), ["index.html"]);
+        let tar = TarAssetSource::open(&dir.path().join("project.tar")).unwrap();
+        assert_eq!(tar.entries.keys().collect::<Vec<_>>(), ["index.html"]);
+    }
+
+    #[test]
+    fn headers_match_fs_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let files: [(&str, &[u8]); 3] = [
+            ("index.html", b"<html></html>"),
+            ("assets/app.js", SCRIPT),
+            ("assets/logo.svg", b"<svg></svg>"),
+        ];
+        let zip_path = dir.path().join("project.zip");
+        write_zip(
+            &zip_path,
+            &files.map(|(name, content)| (name, content, CompressionMethod::Deflated)),
+        );
+
+        // 解压到目录，修改时间与压缩包一致时 ETag 也应一致
+        let modified = std::fs::metadata(&zip_path).unwrap().modified().unwrap();
+        let extracted = dir.path().join("extracted");
+        for (name, content) in files {
+            let path = extracted.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, content).unwrap();
+            File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        }
+
+        let mut resolver = ProtocolResolver::new();
+        resolver.mount("fs", ProjectMount::new(FsAssetSource::new(&extracted)));
+        resolver.mount("zip", ProjectMount::new(ZipAssetSource::open(&zip_path).unwrap()));
+        for (name, _) in files {
+            let get = |id: &str| {
+                let request = Request::builder()
+                    .uri(format!("myapp://{}/{}", id, name))
+                    .body(Vec::new())
+                    .unwrap();
+                resolver.resolve(&request)
+            };
+            let (fs, zip) = (get("fs"), get("zip"));
+            assert_eq!(zip.status(), 200, "{}", name);
+            assert_eq!(zip.body(), fs.body(), "{}", name);
+            for header in [header::CONTENT_TYPE, header::ETAG, header::LAST_MODIFIED, header::CACHE_CONTROL] {
+                assert_eq!(zip.headers().get(&header), fs.headers().get(&header), "{} {}", name, header);
+            }
+            assert!(zip.headers().contains_key(header::ETAG), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+// 内存中的资源缓存：按项目和路径缓存文件内容及预先计算的 MIME、ETag（HTML 缓存注入后的内容）
+// 总大小有上限，超出时淘汰最久未使用的条目；文件大小或修改时间变化时视为失效
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use serde::Serialize;
+
+use super::AssetMetadata;
+
+// 默认缓存上限
+pub const DEFAULT_CAPACITY: u64 = 64 * 1024 * 1024;
+
+// 单个文件最多占上限的 1/8，避免大文件（视频等）把其他条目全部挤出
+const MAX_ENTRY_FRACTION: u64 = 8;
+
+// 一个缓存的资源
+#[derive(Debug)]
+pub struct CachedAsset {
+    pub content: Vec<u8>,
+    pub mime_type: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    // 缓存时的文件大小和修改时间，用于判断是否失效
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl CachedAsset {
+    pub fn new(
+        content: Vec<u8>,
+        mime_type: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        metadata: &AssetMetadata,
+    ) -> Self {
+        Self {
+            content,
+            mime_type,
+            etag,
+            last_modified,
+            len: metadata.len,
+            modified: metadata.modified,
+        }
+    }
+
+    // 没有修改时间的来源无法判断文件是否变化，不缓存
+    fn is_fresh(&self, metadata: &AssetMetadata) -> bool {
+        metadata.modified.is_some() && self.len == metadata.len && self.modified == metadata.modified
+    }
+}
+
+// 命中率等统计，通过 asset_cache_stats 命令返回给前端
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    // 因超出上限被淘汰
+    pub evictions: u64,
+    // 因文件变化、项目重新挂载或文件监听而失效
+    pub invalidations: u64,
+    pub entries: usize,
+    // 当前缓存的字节数
+    pub size: u64,
+    pub capacity: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    // (项目 ID, 路径) -> (资源, 最近使用序号)
+    entries: HashMap<(String, String), (Arc<CachedAsset>, u64)>,
+    // 最近使用序号 -> 键，序号最小的最久未使用
+    recency: BTreeMap<u64, (String, String)>,
+    tick: u64,
+    stats: AssetCacheStats,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: &(String, String)) -> Option<Arc<CachedAsset>> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (asset, used) = self.entries.get_mut(key)?;
+        self.recency.remove(used);
+        *used = tick;
+        self.recency.insert(tick, key.clone());
+        Some(asset.clone())
+    }
+
+    fn remove(&mut self, key: &(String, String)) -> bool {
+        match self.entries.remove(key) {
+            Some((asset, used)) => {
+                self.recency.remove(&used);
+                self.stats.size -= asset.content.len() as u64;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // 淘汰最久未使用的条目，直到总大小不超过上限
+    fn shrink_to(&mut self, capacity: u64) {
+        while self.stats.size > capacity {
+            let Some((_, key)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some((asset, _)) = self.entries.remove(&key) {
+                self.stats.size -= asset.content.len() as u64;
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+// 所有项目共用一个缓存，上限为 0 时不缓存
+pub struct AssetCache {
+    state: Mutex<CacheState>,
+}
+
+impl Default for AssetCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl AssetCache {
+    pub fn new(capacity: u64) -> Self {
+        let mut state = CacheState::default();
+        state.stats.capacity = capacity;
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.state.lock().unwrap().stats.capacity
+    }
+
+    // 调整上限（运行时生效），缩小时立即淘汰超出的条目
+    pub fn set_capacity(&self, capacity: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.stats.capacity = capacity;
+        state.shrink_to(capacity);
+    }
+
+    // 查找缓存；文件已变化时移除旧条目
+    // 找不到时不计为未命中：分段请求、304 和预压缩文件的探测不会读取整个文件，未命中在 insert 时计数
+    pub fn get(&self, project_id: &str, path: &str, metadata: &AssetMetadata) -> Option<Arc<CachedAsset>> {
+        let mut state = self.state.lock().unwrap();
+        let key = (project_id.to_string(), path.to_string());
+        match state.touch(&key) {
+            Some(asset) if asset.is_fresh(metadata) => {
+                state.stats.hits += 1;
+                Some(asset)
+            }
+            Some(_) => {
+                state.remove(&key);
+                state.stats.invalidations += 1;
+                None
+            }
+            None => None,
+        }
+    }
+
+    // 放入缓存（读取了完整文件的未命中），返回共享的资源；过大或无法判断是否失效的文件不缓存
+    pub fn insert(&self, project_id: &str, path: &str, asset: CachedAsset) -> Arc<CachedAsset> {
+        let asset = Arc::new(asset);
+        let mut state = self.state.lock().unwrap();
+        state.stats.misses += 1;
+        let capacity = state.stats.capacity;
+        let size = asset.content.len() as u64;
+        if capacity == 0 || asset.modified.is_none() || size > capacity / MAX_ENTRY_FRACTION {
+            return asset;
+        }
+
+        let key = (project_id.to_string(), path.to_string());
+        state.remove(&key);
+        state.tick += 1;
+        let tick = state.tick;
+        state.recency.insert(tick, key.clone());
+        state.entries.insert(key, (asset.clone(), tick));
+        state.stats.size += size;
+        state.shrink_to(capacity);
+        asset
+    }
+
+    // 移除一个项目的全部条目（重新挂载、切换版本或文件变化后）
+    pub fn invalidate_project(&self, project_id: &str) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let keys: Vec<(String, String)> = state
+            .entries
+            .keys()
+            .filter(|(id, _)| id == project_id)
+            .cloned()
+            .collect();
+        for key in &keys {
+            state.remove(key);
+        }
+        state.stats.invalidations += keys.len() as u64;
+        keys.len()
+    }
+
+    // 清空缓存，统计数据保留
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        let count = state.entries.len() as u64;
+        state.entries.clear();
+        state.recency.clear();
+        state.stats.size = 0;
+        state.stats.invalidations += count;
+    }
+
+    pub fn stats(&self) -> AssetCacheStats {
+        let state = self.state.lock().unwrap();
+        AssetCacheStats {
+            entries: state.entries.len(),
+            ..state.stats.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn metadata(len: usize, modified_secs: u64) -> AssetMetadata {
+        AssetMetadata {
+            len: len as u64,
+            modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(modified_secs)),
+        }
+    }
+
+    fn asset(size: usize) -> CachedAsset {
+        CachedAsset::new(vec![b'x'; size], "text/plain".to_string(), None, None, &metadata(size, 1))
+    }
+
+    fn cached(cache: &AssetCache, project_id: &str, path: &str, size: usize) -> bool {
+        cache.get(project_id, path, &metadata(size, 1)).is_some()
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = AssetCache::new(800);
+        for path in ["a", "b", "c", "d"] {
+            cache.insert("p", path, asset(100));
+        }
+        // 访问 a 之后 b 成为最久未使用的条目
+        assert!(cached(&cache, "p", "a", 100));
+        for path in ["e", "f", "g", "h", "i"] {
+            cache.insert("p", path, asset(100));
+        }
+        assert!(!cached(&cache, "p", "b", 100));
+        assert!(cached(&cache, "p", "a", 100));
+        assert!(cached(&cache, "p", "i", 100));
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.size, stats.evictions), (8, 800, 1));
+
+        // 缩小上限时按最久未使用的顺序淘汰：c、d 最早
+        cache.set_capacity(600);
+        assert!(!cached(&cache, "p", "c", 100));
+        assert!(!cached(&cache, "p", "d", 100));
+        assert!(cached(&cache, "p", "e", 100));
+        assert_eq!(cache.stats().evictions, 3);
+    }
+
+    #[test]
+    fn large_or_undatable_entries_are_not_cached() {
+        let cache = AssetCache::new(800);
+        // 单个条目最多为上限的 1/8
+        cache.insert("p", "fits", asset(100));
+        cache.insert("p", "too-big", asset(101));
+        assert!(cached(&cache, "p", "fits", 100));
+        assert!(!cached(&cache, "p", "too-big", 101));
+
+        let no_mtime = AssetMetadata { len: 1, modified: None };
+        cache.insert("p", "no-mtime", CachedAsset::new(vec![1], String::new(), None, None, &no_mtime));
+        assert!(cache.get("p", "no-mtime", &no_mtime).is_none());
+
+        let disabled = AssetCache::new(0);
+        disabled.insert("p", "a", asset(1));
+        assert_eq!(disabled.stats().entries, 0);
+    }
+
+    #[test]
+    fn changed_file_invalidates_entry() {
+        let cache = AssetCache::new(800);
+        cache.insert("p", "a", asset(10));
+        assert!(cache.get("p", "a", &metadata(10, 2)).is_none());
+        // 失效的条目已移除，即使元数据恢复也不会再命中
+        assert!(cache.get("p", "a", &metadata(10, 1)).is_none());
+        cache.insert("p", "b", asset(10));
+        assert!(cache.get("p", "b", &metadata(11, 1)).is_none());
+        let stats = cache.stats();
+        assert_eq!((stats.invalidations, stats.entries, stats.size), (2, 0, 0));
+    }
+
+    #[test]
+    fn invalidate_by_project() {
+        let cache = AssetCache::new(800);
+        cache.insert("p", "a", asset(10));
+        cache.insert("p", "b", asset(10));
+        cache.insert("q", "a", asset(10));
+        assert_eq!(cache.invalidate_project("p"), 2);
+        assert!(!cached(&cache, "p", "a", 10));
+        assert!(cached(&cache, "q", "a", 10));
+        assert_eq!(cache.invalidate_project("missing"), 0);
+        cache.clear();
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.size, stats.invalidations), (0, 0, 3));
+    }
+
+    #[test]
+    fn misses_count_fills_only() {
+        let cache = AssetCache::new(800);
+        assert!(!cached(&cache, "p", "a", 10));
+        assert_eq!(cache.stats().misses, 0);
+        cache.insert("p", "a", asset(10));
+        assert!(cached(&cache, "p", "a", 10));
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+}
@@ -0,0 +1,271 @@
+// 缓存相关：ETag / Last-Modified 校验、304 条件响应和 Cache-Control 策略
+use std::time::SystemTime;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tauri::http::{header, HeaderMap};
+
+use super::AssetMetadata;
+
+// Cache-Control 策略，按文件类型选择
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CachePolicy {
+    // HTML 文档（入口文件会变化，每次都要重新验证）
+    pub document: String,
+    // 文件名带内容哈希的资源，例如 index-204389a5.js
+    pub immutable: String,
+    // 其他资源
+    pub default: String,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            document: "no-cache".to_string(),
+            immutable: "public, max-age=31536000, immutable".to_string(),
+            default: "no-cache".to_string(),
+        }
+    }
+}
+
+impl CachePolicy {
+    pub fn cache_control(&self, path: &str, mime_type: &str) -> &str {
+        if super::mime::essence(mime_type) == "text/html" {
+            &self.document
+        } else if is_content_hashed(path) {
+            &self.immutable
+        } else {
+            &self.default
+        }
+    }
+}
+
+// 文件名是否带内容哈希：主文件名按 "-" 或 "." 分割后最后一段是哈希
+//   十六进制，至少 8 位：index-204389a5.js、logotype-light.9b4661b9f6d633720346a28bf03b2f25.png
+//   base64url（Vite / Rollup 默认），至少 8 位：index-B3xK_9aZ.js，哈希本身也可能含 "-"，如 vendor-Dk-4x_Qa.js
+pub fn is_content_hashed(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let stem = match file_name.rsplit_once('.') {
+        Some((stem, _)) => stem,
+        None => return false,
+    };
+    let (rest, hash) = stem.rsplit_once(['-', '.']).unwrap_or(("", stem));
+    if hash.len() >= 8 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return true;
+    }
+    if rest.is_empty() {
+        return false;
+    }
+    // Rollup 的哈希固定为 8 位，含 "-" 时取主文件名的最后 8 个字符
+    let tail = stem
+        .len()
+        .checked_sub(8)
+        .filter(|&start| stem.get(..start).is_some_and(|name| name.ends_with(['-', '.'])))
+        .and_then(|start| stem.get(start..));
+    is_base64_hash(hash) || tail.is_some_and(is_base64_hash)
+}
+
+// base64url 哈希：由字母、数字、"_"、"-" 组成；普通单词（app-settings、app-Settings）不算，
+// 误判会让未带哈希的文件被长期缓存，因此要求含数字或首字母之后的大写字母
+fn is_base64_hash(segment: &str) -> bool {
+    segment.len() >= 8
+        && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && (segment.chars().any(|c| c.is_ascii_digit()) || segment.chars().skip(1).any(|c| c.is_ascii_uppercase()))
+}
+
+// 基于修改时间和大小的强 ETag
+pub fn metadata_etag(metadata: &AssetMetadata) -> Option<String> {
+    let modified = metadata.modified?;
+    let nanos = modified
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    Some(format!("\"{:x}-{:x}\"", metadata.len, nanos))
+}
+
+// 基于内容哈希的强 ETag，用于注入后的 HTML 等动态内容
+pub fn content_etag(content: &[u8]) -> String {
+    let digest = Sha256::digest(content);
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+// Last-Modified 头的值
+pub fn last_modified(metadata: &AssetMetadata) -> Option<String> {
+    metadata.modified.map(httpdate::fmt_http_date)
+}
+
+// 根据 If-None-Match / If-Modified-Since 判断是否可以返回 304
+// 同时存在时以 If-None-Match 为准
+pub fn is_not_modified(headers: &HeaderMap, etag: Option<&str>, modified: Option<SystemTime>) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+        let etag = match etag {
+            Some(etag) => etag,
+            None => return false,
+        };
+        // If-None-Match 使用弱比较，忽略 W/ 前缀
+        return if_none_match.trim() == "*"
+            || if_none_match
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == etag);
+    }
+
+    match (
+        headers.get(header::IF_MODIFIED_SINCE).and_then(|v| v.to_str().ok()),
+        modified,
+    ) {
+        (Some(since), Some(modified)) => match httpdate::parse_http_date(since) {
+            // HTTP 日期只精确到秒
+            Ok(since) => truncate_to_secs(modified) <= since,
+            Err(_) => false,
+        },
+        _ => false,
+    }
+}
+
+// If-Range：只有验证器仍然匹配时才按 Range 返回分段，否则返回完整内容
+pub fn if_range_matches(headers: &HeaderMap, etag: Option<&str>, modified: Option<SystemTime>) -> bool {
+    let if_range = match headers.get(header::IF_RANGE).and_then(|v| v.to_str().ok()) {
+        Some(if_range) => if_range.trim(),
+        None => return true,
+    };
+
+    if if_range.starts_with('"') {
+        // If-Range 使用强比较
+        return etag == Some(if_range);
+    }
+    match (httpdate::parse_http_date(if_range), modified) {
+        (Ok(date), Some(modified)) => truncate_to_secs(modified) == date,
+        _ => false,
+    }
+}
+
+fn truncate_to_secs(time: SystemTime) -> SystemTime {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(duration) => SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(duration.as_secs()),
+        Err(_) => time,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tauri::http::HeaderValue;
+
+    const ETAG: &str = "\"abc\"";
+
+    fn headers(pairs: &[(header::HeaderName, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn modified() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_500)
+    }
+
+    #[test]
+    fn if_none_match() {
+        let matches = |value: &str| is_not_modified(&headers(&[(header::IF_NONE_MATCH, value)]), Some(ETAG), None);
+        assert!(matches("\"abc\""));
+        // 弱比较：W/ 前缀不影响
+        assert!(matches("W/\"abc\""));
+        assert!(matches("*"));
+        assert!(matches("\"x\", W/\"abc\" ,\"y\""));
+        assert!(!matches("\"abcd\""));
+        assert!(!matches("\"x\", \"y\""));
+        // 没有 ETag 时不能返回 304
+        assert!(!is_not_modified(&headers(&[(header::IF_NONE_MATCH, "*")]), None, None));
+    }
+
+    #[test]
+    fn if_modified_since() {
+        let same_second = httpdate::fmt_http_date(modified());
+        let later = httpdate::fmt_http_date(modified() + Duration::from_secs(60));
+        let earlier = httpdate::fmt_http_date(modified() - Duration::from_secs(60));
+        let check = |since: &str| is_not_modified(&headers(&[(header::IF_MODIFIED_SINCE, since)]), Some(ETAG), Some(modified()));
+        // 修改时间的毫秒部分被截掉
+        assert!(check(&same_second));
+        assert!(check(&later));
+        assert!(!check(&earlier));
+        assert!(!check("not a date"));
+        assert!(!is_not_modified(&headers(&[(header::IF_MODIFIED_SINCE, &later)]), Some(ETAG), None));
+    }
+
+    #[test]
+    fn if_none_match_takes_precedence() {
+        let later = httpdate::fmt_http_date(modified() + Duration::from_secs(60));
+        let headers = headers(&[(header::IF_NONE_MATCH, "\"other\""), (header::IF_MODIFIED_SINCE, &later)]);
+        assert!(!is_not_modified(&headers, Some(ETAG), Some(modified())));
+        assert!(!is_not_modified(&HeaderMap::new(), Some(ETAG), Some(modified())));
+    }
+
+    #[test]
+    fn if_range_validators() {
+        let date = httpdate::fmt_http_date(modified());
+        let check = |value: &str| if_range_matches(&headers(&[(header::IF_RANGE, value)]), Some(ETAG), Some(modified()));
+        assert!(if_range_matches(&HeaderMap::new(), Some(ETAG), None));
+        assert!(check(ETAG));
+        assert!(check(&date));
+        // 强比较：弱 ETag 不匹配
+        assert!(!check("W/\"abc\""));
+        assert!(!check("\"other\""));
+        assert!(!check(&httpdate::fmt_http_date(modified() + Duration::from_secs(1))));
+    }
+
+    #[test]
+    fn content_hashed_file_names() {
+        for path in [
+            "assets/index-204389a5.js",
+            "logotype-light.9b4661b9f6d633720346a28bf03b2f25.png",
+            "chunk.DEADBEEF.css",
+            // Vite 默认的 base64url 哈希
+            "assets/index-B3xK_9aZ.js",
+            "assets/index-CwXyZpQr.css",
+            "assets/vendor-Dk-4x_Qa.js",
+            "assets/Settings-a1b2c3d4e5.js",
+        ] {
+            assert!(is_content_hashed(path), "{}", path);
+        }
+        for path in [
+            "index.html",
+            "app.js",
+            "assets/vendor-react.js",
+            "abc1234.js",
+            "deadbeef",
+            "main-2024.js",
+            "assets/app-settings.js",
+            "assets/app-Settings.js",
+            "assets/components-dashboard.js",
+            "MyComponent.js",
+            "B3xK_9aZ",
+        ] {
+            assert!(!is_content_hashed(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn cache_control_by_kind() {
+        let policy = CachePolicy::default();
+        assert_eq!(policy.cache_control("index-204389a5.html", "text/html; charset=utf-8"), "no-cache");
+        assert_eq!(policy.cache_control("index-204389a5.js", "text/javascript"), policy.immutable);
+        assert_eq!(policy.cache_control("app.js", "text/javascript"), "no-cache");
+    }
+
+    #[test]
+    fn etags() {
+        let metadata = AssetMetadata {
+            len: 10,
+            modified: Some(modified()),
+        };
+        let etag = metadata_etag(&metadata).unwrap();
+        assert!(etag.starts_with("\"a-") && etag.ends_with('"'));
+        assert_eq!(metadata_etag(&AssetMetadata { len: 10, modified: None }), None);
+        assert_ne!(content_etag(b"a"), content_etag(b"b"));
+        assert_eq!(content_etag(b"a"), content_etag(b"a"));
+    }
+}
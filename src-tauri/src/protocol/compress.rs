@@ -0,0 +1,129 @@
+// 响应压缩：优先使用 dist 中预压缩的 .br / .gz 文件，否则按配置即时压缩文本类型
+use std::io::Write;
+
+use serde::Deserialize;
+use tauri::http::{header, HeaderMap};
+
+// 支持的内容编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    // Content-Encoding 头的值
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    // 预压缩文件的扩展名，例如 foo.js.br
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gz",
+        }
+    }
+
+    // 即时压缩
+    pub fn compress(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Brotli => {
+                let mut output = Vec::new();
+                {
+                    // 即时压缩使用中等质量，兼顾速度和压缩率
+                    let mut writer = brotli::CompressorWriter::new(&mut output, 4096, 5, 22);
+                    writer.write_all(data)?;
+                }
+                Ok(output)
+            }
+            Encoding::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+// 压缩配置
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CompressionConfig {
+    // 是否使用同目录下的 .br / .gz 预压缩文件
+    pub precompressed: bool,
+    // 没有预压缩文件时是否即时压缩文本类型
+    pub on_the_fly: bool,
+    // 即时压缩的最小文件大小（字节），太小的文件压缩得不偿失
+    pub min_size: u64,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            precompressed: true,
+            on_the_fly: false,
+            min_size: 1024,
+        }
+    }
+}
+
+// 解析 Accept-Encoding，返回客户端接受的编码（按 q 值从高到低，相同时 br 优先）
+pub fn accepted_encodings(headers: &HeaderMap) -> Vec<Encoding> {
+    let value = match headers.get(header::ACCEPT_ENCODING).and_then(|v| v.to_str().ok()) {
+        Some(value) => value,
+        None => return Vec::new(),
+    };
+
+    let mut brotli = None;
+    let mut gzip = None;
+    let mut wildcard = None;
+    for item in value.split(',') {
+        let mut parts = item.split(';');
+        let name = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+        let quality = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        match name.as_str() {
+            "br" => brotli = Some(quality),
+            "gzip" | "x-gzip" => gzip = Some(quality),
+            "*" => wildcard = Some(quality),
+            _ => {}
+        }
+    }
+
+    let mut accepted: Vec<(Encoding, f32)> = [
+        (Encoding::Brotli, brotli.or(wildcard)),
+        (Encoding::Gzip, gzip.or(wildcard)),
+    ]
+    .into_iter()
+    .filter_map(|(encoding, quality)| quality.filter(|q| *q > 0.0).map(|q| (encoding, q)))
+    .collect();
+    // 稳定排序，q 值相同时保持 br 在前
+    accepted.sort_by(|a, b| b.1.total_cmp(&a.1));
+    accepted.into_iter().map(|(encoding, _)| encoding).collect()
+}
+
+// 是否为值得压缩的文本类型
+pub fn is_compressible(mime_type: &str) -> bool {
+    let essence = super::mime::essence(mime_type);
+    essence.starts_with("text/")
+        || matches!(
+            essence,
+            "application/javascript"
+                | "application/json"
+                | "application/manifest+json"
+                | "application/xml"
+                | "image/svg+xml"
+        )
+}
+
+// 为压缩后的表示生成独立的 ETag，例如 "abc" -> "abc-br"
+pub fn encoded_etag(etag: &str, encoding: Encoding) -> String {
+    format!("\"{}-{}\"", etag.trim_matches('"'), encoding.name())
+}
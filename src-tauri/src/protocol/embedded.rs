@@ -0,0 +1,281 @@
+// 内嵌资源：启用 embedded-assets 特性时，build.rs 把资源目录打包为 zip 编译进可执行文件
+// 启动时建立一次索引，每个项目以其目录为前缀读取，不依赖资源目录
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use zip::{CompressionMethod, ZipArchive};
+
+use super::source::{normalize_path, AssetError, AssetMetadata, AssetSource};
+
+struct EmbeddedEntry {
+    index: usize,
+    size: u64,
+    // 未压缩（Stored）条目在内嵌数据中的起始位置，直接切片返回
+    stored_at: Option<usize>,
+}
+
+// 内嵌的资源包
+pub struct EmbeddedStore {
+    bytes: &'static [u8],
+    // 构建时间，作为所有文件的修改时间，每次构建后 ETag 随之变化
+    modified: Option<SystemTime>,
+    entries: HashMap<String, EmbeddedEntry>,
+    archive: ZipArchive<Cursor<&'static [u8]>>,
+}
+
+impl EmbeddedStore {
+    pub fn new(bytes: &'static [u8], modified: Option<SystemTime>) -> Result<Self, String> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("内嵌资源包无效: {}", e))?;
+        let mut entries = HashMap::new();
+        for index in 0..archive.len() {
+            let entry = archive
+                .by_index_raw(index)
+                .map_err(|e| format!("读取内嵌资源失败: {}", e))?;
+            if entry.is_dir() {
+                continue;
+            }
+            let Some(name) = normalize_path(entry.name()) else {
+                continue;
+            };
+            let stored_at = (entry.compression() == CompressionMethod::Stored).then(|| entry.data_start() as usize);
+            entries.insert(
+                name,
+                EmbeddedEntry {
+                    index,
+                    size: entry.size(),
+                    stored_at,
+                },
+            );
+        }
+        Ok(Self {
+            bytes,
+            modified,
+            entries,
+            archive,
+        })
+    }
+
+    // 编译进可执行文件的资源包；没有启用 embedded-assets 特性时为 None
+    pub fn bundled() -> Option<Arc<EmbeddedStore>> {
+        #[cfg(feature = "embedded-assets")]
+        {
+            use std::sync::OnceLock;
+            use std::time::{Duration, UNIX_EPOCH};
+
+            static STORE: OnceLock<Option<Arc<EmbeddedStore>>> = OnceLock::new();
+            STORE
+                .get_or_init(|| {
+                    let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/embedded-resources.zip"));
+                    let modified = env!("EMBEDDED_ASSETS_BUILT_AT")
+                        .parse()
+                        .ok()
+                        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+                    match EmbeddedStore::new(bytes, modified) {
+                        Ok(store) => {
+                            println!("📦 使用内嵌资源: {} 个文件", store.len());
+                            Some(Arc::new(store))
+                        }
+                        Err(e) => {
+                            println!("❌ {}", e);
+                            None
+                        }
+                    }
+                })
+                .clone()
+        }
+        #[cfg(not(feature = "embedded-assets"))]
+        {
+            None
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        normalize_path(path).is_some_and(|name| self.entries.contains_key(&name))
+    }
+
+    // 顶层目录名，按名称排序
+    pub fn top_level_dirs(&self) -> Vec<String> {
+        let mut dirs: Vec<String> = self
+            .entries
+            .keys()
+            .filter_map(|name| name.split_once('/').map(|(dir, _)| dir.to_string()))
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+
+    fn entry(&self, name: &str) -> Result<&EmbeddedEntry, AssetError> {
+        self.entries.get(name).ok_or(AssetError::NotFound)
+    }
+
+    // 未压缩条目的 [start, end) 区间
+    fn stored(&self, offset: usize, start: u64, end: u64) -> Result<Vec<u8>, AssetError> {
+        self.bytes
+            .get(offset + start as usize..offset + end as usize)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| AssetError::Io(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)))
+    }
+
+    pub fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let name = normalize_path(path).ok_or(AssetError::Forbidden)?;
+        let entry = self.entry(&name)?;
+        if let Some(offset) = entry.stored_at {
+            return self.stored(offset, 0, entry.size);
+        }
+        // ZipArchive 克隆时共享索引，不需要加锁
+        let mut archive = self.archive.clone();
+        let mut file = archive
+            .by_index(entry.index)
+            .map_err(|e| AssetError::Io(std::io::Error::other(e.to_string())))?;
+        let mut content = Vec::with_capacity(entry.size as usize);
+        file.read_to_end(&mut content)?;
+        Ok(content)
+    }
+}
+
+impl std::fmt::Debug for EmbeddedStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddedStore")
+            .field("size", &self.bytes.len())
+            .field("files", &self.entries.len())
+            .finish()
+    }
+}
+
+// 内嵌资源包中的一个项目目录
+#[derive(Debug, Clone)]
+pub struct EmbeddedAssetSource {
+    store: Arc<EmbeddedStore>,
+    // 项目目录在资源包中的路径，例如 "studio"
+    prefix: String,
+}
+
+impl EmbeddedAssetSource {
+    pub fn new(store: Arc<EmbeddedStore>, prefix: &str) -> Self {
+        Self {
+            store,
+            prefix: normalize_path(prefix).unwrap_or_default(),
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn name(&self, path: &str) -> Result<String, AssetError> {
+        let path = normalize_path(path).ok_or(AssetError::Forbidden)?;
+        Ok(if self.prefix.is_empty() {
+            path
+        } else {
+            format!("{}/{}", self.prefix, path)
+        })
+    }
+}
+
+impl AssetSource for EmbeddedAssetSource {
+    fn metadata(&self, path: &str) -> Result<AssetMetadata, AssetError> {
+        let entry = self.store.entry(&self.name(path)?)?;
+        Ok(AssetMetadata {
+            len: entry.size,
+            modified: self.store.modified,
+        })
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        self.store.read(&self.name(path)?)
+    }
+
+    fn read_range(&self, path: &str, start: u64, len: u64) -> Result<Vec<u8>, AssetError> {
+        let name = self.name(path)?;
+        let entry = self.store.entry(&name)?;
+        let start = start.min(entry.size);
+        let end = start.saturating_add(len).min(entry.size);
+        match entry.stored_at {
+            Some(offset) => self.store.stored(offset, start, end),
+            None => Ok(self.store.read(&name)?[start as usize..end as usize].to_vec()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    // 在内存中构建资源包；EmbeddedStore 需要 'static 数据，测试中直接泄漏这段内存
+    fn store(files: &[(&str, &[u8], CompressionMethod)]) -> Arc<EmbeddedStore> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content, method) in files {
+            let options = zip::write::SimpleFileOptions::default().compression_method(*method);
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        let bytes = writer.finish().unwrap().into_inner().leak();
+        Arc::new(EmbeddedStore::new(bytes, Some(SystemTime::UNIX_EPOCH)).unwrap())
+    }
+
+    fn sample() -> Arc<EmbeddedStore> {
+        store(&[
+            ("studio/index.html", b"<html>studio</html>", CompressionMethod::Deflated),
+            ("studio/assets/app.js", b"console.log(1);", CompressionMethod::Stored),
+            ("other/index.html", b"<html>other</html>", CompressionMethod::Deflated),
+            ("projects.json", b"{}", CompressionMethod::Stored),
+        ])
+    }
+
+    #[test]
+    fn project_prefix_is_stripped() {
+        let store = sample();
+        assert_eq!(store.len(), 4);
+        assert_eq!(store.top_level_dirs(), ["other", "studio"]);
+
+        let studio = EmbeddedAssetSource::new(store.clone(), "studio");
+        assert_eq!(studio.prefix(), "studio");
+        assert_eq!(studio.read("index.html").unwrap(), b"<html>studio</html>");
+        assert_eq!(studio.read("assets/./app.js").unwrap(), b"console.log(1);");
+        assert_eq!(studio.metadata("assets/app.js").unwrap().len, 15);
+        assert_eq!(studio.metadata("assets/app.js").unwrap().modified, Some(SystemTime::UNIX_EPOCH));
+        assert_eq!(studio.read_range("assets/app.js", 8, 3).unwrap(), b"log");
+        assert_eq!(studio.read_range("index.html", 6, 6).unwrap(), b"studio");
+
+        // 前缀同样规范化
+        let other = EmbeddedAssetSource::new(store.clone(), "./other/");
+        assert_eq!(other.prefix(), "other");
+        assert_eq!(other.read("index.html").unwrap(), b"<html>other</html>");
+
+        // 空前缀读取资源包根目录
+        let root = EmbeddedAssetSource::new(store, "");
+        assert_eq!(root.read("projects.json").unwrap(), b"{}");
+        assert_eq!(root.read("studio/index.html").unwrap(), b"<html>studio</html>");
+    }
+
+    #[test]
+    fn missing_entries_are_not_found() {
+        let store = sample();
+        let studio = EmbeddedAssetSource::new(store.clone(), "studio");
+        for path in ["missing.js", "assets", "other/index.html", "projects.json"] {
+            assert!(matches!(studio.read(path), Err(AssetError::NotFound)), "{}", path);
+            assert!(matches!(studio.metadata(path), Err(AssetError::NotFound)), "{}", path);
+            assert!(matches!(studio.read_range(path, 0, 1), Err(AssetError::NotFound)), "{}", path);
+        }
+        // 不能通过 ".." 读取其他项目
+        assert!(matches!(studio.read("../other/index.html"), Err(AssetError::Forbidden)));
+
+        assert!(store.contains("studio/index.html"));
+        assert!(!store.contains("studio"));
+        assert!(!store.contains("../studio/index.html"));
+        assert!(matches!(store.read("studio"), Err(AssetError::NotFound)));
+    }
+}
@@ -0,0 +1,670 @@
+// API 流量的录制与回放（HAR 1.2 格式）
+// 录制：经反向代理转发的每次请求/响应都记录到项目的 HAR 文件（先缓存在内存，定时及停止录制时写入）
+// 回放：按方法、路径、查询参数、请求体哈希匹配 HAR 中的记录并直接返回
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::path::Component;
+use std::time::{Duration, SystemTime};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::http::{header, Request, Response};
+
+// 录制中的记录定时写入 HAR 文件的间隔
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+// 回放时的匹配条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarMatch {
+    #[serde(default = "enabled")]
+    pub method: bool,
+    #[serde(default = "enabled")]
+    pub path: bool,
+    #[serde(default = "enabled")]
+    pub query: bool,
+    // 请求体的 SHA-256 是否一致
+    #[serde(default)]
+    pub body: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for HarMatch {
+    fn default() -> Self {
+        Self {
+            method: true,
+            path: true,
+            query: true,
+            body: false,
+        }
+    }
+}
+
+// ===== HAR 1.2 数据结构（只包含本项目用到的字段） =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    #[serde(default)]
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    pub started_date_time: String,
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    #[serde(default)]
+    pub cache: serde_json::Value,
+    #[serde(default)]
+    pub timings: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    #[serde(default)]
+    pub query_string: Vec<HarNameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    pub content: HarContent,
+    #[serde(default)]
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: String,
+    // 非 UTF-8 请求体以 base64 保存（HAR 扩展字段）
+    #[serde(default, rename = "_encoding", skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    // 非 UTF-8 内容为 "base64"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+impl Har {
+    pub fn new() -> Self {
+        Self {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries: Vec::new(),
+            },
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取 HAR 文件失败 {:?}: {}", path, e))?;
+        serde_json::from_str(&content).map_err(|e| format!("解析 HAR 文件失败 {:?}: {}", path, e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        write_file(path, &self.to_bytes()?)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec_pretty(self).map_err(|e| format!("序列化 HAR 失败: {}", e))
+    }
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建 HAR 目录失败 {:?}: {}", parent, e))?;
+    }
+    std::fs::write(path, content).map_err(|e| format!("写入 HAR 文件失败 {:?}: {}", path, e))
+}
+
+// 项目的 HAR 文件路径：{har_dir}/{project_id}/{name}，没有 .har 扩展名时自动补上
+// name 来自页面（项目页面也能调用 IPC），只接受单个文件名，不能包含路径分隔符或 ".."
+pub fn har_path(har_dir: &Path, project_id: &str, name: &str) -> Result<PathBuf, String> {
+    for part in [project_id, name] {
+        if !is_file_name(part) {
+            return Err(format!("非法的 HAR 文件名: {}", part));
+        }
+    }
+    let name = if name.to_ascii_lowercase().ends_with(".har") {
+        name.to_string()
+    } else {
+        format!("{}.har", name)
+    };
+    Ok(har_dir.join(project_id).join(name))
+}
+
+fn is_file_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    !name.contains(['/', '\\', ':', '\0'])
+        && matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+}
+
+impl Default for Har {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 录制/回放状态
+enum HarState {
+    Off,
+    // dirty 表示有尚未写入文件的记录
+    Recording { path: PathBuf, har: Har, dirty: bool },
+    Replaying { path: PathBuf, har: Har, matching: HarMatch, used: Vec<bool> },
+}
+
+// 一个项目的录制/回放会话
+pub struct HarSession {
+    state: Mutex<HarState>,
+    // 写文件时持有，保证较早的快照不会覆盖较新的；写文件期间不占用 state，不阻塞请求
+    writing: Mutex<()>,
+}
+
+impl Default for HarSession {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(HarState::Off),
+            writing: Mutex::new(()),
+        }
+    }
+}
+
+impl HarSession {
+    // 开始录制到指定文件（会结束当前的录制或回放）
+    pub fn start_recording(&self, path: PathBuf) -> Result<(), String> {
+        self.finish_recording();
+        let har = Har::new();
+        har.save(&path)?;
+        println!("⏺️ 开始录制 API 流量: {:?}", path);
+        *self.state.lock().unwrap() = HarState::Recording {
+            path,
+            har,
+            dirty: false,
+        };
+        Ok(())
+    }
+
+    // 停止录制并写入全部记录，返回写入的文件路径；当前没有在录制时返回 None
+    pub fn stop_recording(&self) -> Result<Option<PathBuf>, String> {
+        let _writing = self.writing.lock().unwrap();
+        let (path, content, count) = {
+            let mut state = self.state.lock().unwrap();
+            let HarState::Recording { path, har, .. } = &*state else {
+                return Ok(None);
+            };
+            let snapshot = (path.clone(), har.to_bytes()?, har.log.entries.len());
+            *state = HarState::Off;
+            snapshot
+        };
+        write_file(&path, &content)?;
+        println!("⏹️ 停止录制，共 {} 条: {:?}", count, path);
+        Ok(Some(path))
+    }
+
+    // 把尚未写入的记录写入 HAR 文件（由定时任务调用），没有新记录时不写
+    pub fn flush(&self) -> Result<(), String> {
+        let _writing = self.writing.lock().unwrap();
+        let (path, content) = {
+            let mut state = self.state.lock().unwrap();
+            match &mut *state {
+                HarState::Recording { path, har, dirty } if *dirty => {
+                    *dirty = false;
+                    (path.clone(), har.to_bytes()?)
+                }
+                _ => return Ok(()),
+            }
+        };
+        write_file(&path, &content)
+    }
+
+    // 切换到其他状态前结束正在进行的录制，避免丢失尚未写入的记录
+    fn finish_recording(&self) {
+        if let Err(e) = self.stop_recording() {
+            println!("⚠️ {}", e);
+        }
+    }
+
+    // 使用指定 HAR 文件回放，返回记录条数；path 为 None 时关闭回放
+    pub fn set_replay(&self, path: Option<PathBuf>, matching: HarMatch) -> Result<usize, String> {
+        let path = match path {
+            Some(path) => path,
+            None => {
+                let mut state = self.state.lock().unwrap();
+                if matches!(&*state, HarState::Replaying { .. }) {
+                    *state = HarState::Off;
+                }
+                return Ok(0);
+            }
+        };
+        // 先写入录制中的记录，回放的可能正是正在录制的文件
+        self.flush()?;
+        let har = Har::load(&path)?;
+        self.finish_recording();
+        let mut state = self.state.lock().unwrap();
+        let count = har.log.entries.len();
+        println!("▶️ 回放 API 流量，共 {} 条: {:?}", count, path);
+        *state = HarState::Replaying {
+            path,
+            har,
+            matching,
+            used: vec![false; count],
+        };
+        Ok(count)
+    }
+
+    // 当前状态描述："off"、"recording"、"replaying"，以及对应的文件
+    pub fn status(&self) -> (&'static str, Option<PathBuf>) {
+        match &*self.state.lock().unwrap() {
+            HarState::Off => ("off", None),
+            HarState::Recording { path, .. } => ("recording", Some(path.clone())),
+            HarState::Replaying { path, .. } => ("replaying", Some(path.clone())),
+        }
+    }
+
+    // 回放：查找匹配的记录，优先使用尚未回放过的，全部用过后重复最后一条
+    pub fn replay(&self, request: &Request<Vec<u8>>) -> Option<Response<Vec<u8>>> {
+        let mut state = self.state.lock().unwrap();
+        let (har, matching, used) = match &mut *state {
+            HarState::Replaying { har, matching, used, .. } => (har, matching, used),
+            _ => return None,
+        };
+
+        let candidates: Vec<usize> = har
+            .log
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry_matches(&entry.request, request, matching))
+            .map(|(index, _)| index)
+            .collect();
+        let index = candidates
+            .iter()
+            .copied()
+            .find(|index| !used[*index])
+            .or_else(|| candidates.last().copied())?;
+        used[index] = true;
+
+        println!("▶️ 回放命中: {} {}", request.method(), request.uri());
+        to_response(&har.log.entries[index].response)
+    }
+
+    // 录制一次经代理转发的请求
+    pub fn record(
+        &self,
+        request: &Request<Vec<u8>>,
+        response: &Response<Vec<u8>>,
+        started: SystemTime,
+        elapsed: Duration,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        if let HarState::Recording { har, dirty, .. } = &mut *state {
+            // 只记录在内存中，由 flush 定时写入，避免每个请求都在持有锁时重写整个文件
+            har.log.entries.push(to_entry(request, response, started, elapsed));
+            *dirty = true;
+        }
+    }
+}
+
+// 会话释放时（例如项目被移除）写入尚未写入的记录
+impl Drop for HarSession {
+    fn drop(&mut self) {
+        self.finish_recording();
+    }
+}
+
+fn entry_matches(recorded: &HarRequest, request: &Request<Vec<u8>>, matching: &HarMatch) -> bool {
+    if matching.method && !recorded.method.eq_ignore_ascii_case(request.method().as_str()) {
+        return false;
+    }
+    let recorded_uri: tauri::http::Uri = match recorded.url.parse() {
+        Ok(uri) => uri,
+        Err(_) => return false,
+    };
+    if matching.path && recorded_uri.path() != request.uri().path() {
+        return false;
+    }
+    if matching.query && sorted_query(recorded_uri.query()) != sorted_query(request.uri().query()) {
+        return false;
+    }
+    if matching.body {
+        let recorded_body = recorded
+            .post_data
+            .as_ref()
+            .map(decode_post_data)
+            .unwrap_or_default();
+        if body_hash(&recorded_body) != body_hash(request.body()) {
+            return false;
+        }
+    }
+    true
+}
+
+// 查询参数排序后比较，忽略参数顺序
+fn sorted_query(query: Option<&str>) -> Vec<String> {
+    let mut pairs: Vec<String> = query
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(str::to_string)
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+fn body_hash(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+fn decode_post_data(post_data: &HarPostData) -> Vec<u8> {
+    match post_data.encoding.as_deref() {
+        Some("base64") => base64::engine::general_purpose::STANDARD
+            .decode(&post_data.text)
+            .unwrap_or_default(),
+        _ => post_data.text.clone().into_bytes(),
+    }
+}
+
+// 文本以原样保存，二进制以 base64 保存
+fn encode_body(body: &[u8]) -> (String, Option<String>) {
+    match std::str::from_utf8(body) {
+        Ok(text) => (text.to_string(), None),
+        Err(_) => (
+            base64::engine::general_purpose::STANDARD.encode(body),
+            Some("base64".to_string()),
+        ),
+    }
+}
+
+fn name_values(headers: &tauri::http::HeaderMap) -> Vec<HarNameValue> {
+    headers
+        .iter()
+        .map(|(name, value)| HarNameValue {
+            name: name.to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        })
+        .collect()
+}
+
+fn to_entry(
+    request: &Request<Vec<u8>>,
+    response: &Response<Vec<u8>>,
+    started: SystemTime,
+    elapsed: Duration,
+) -> HarEntry {
+    let header_value = |headers: &tauri::http::HeaderMap, name: header::HeaderName| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    let post_data = if request.body().is_empty() {
+        None
+    } else {
+        let (text, encoding) = encode_body(request.body());
+        Some(HarPostData {
+            mime_type: header_value(request.headers(), header::CONTENT_TYPE),
+            text,
+            encoding,
+        })
+    };
+    let query_string = request
+        .uri()
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            HarNameValue {
+                name: name.to_string(),
+                value: value.to_string(),
+            }
+        })
+        .collect();
+
+    // 响应体保存的是上游返回的原始字节（如果带 Content-Encoding 则为压缩后的内容）
+    let (text, encoding) = encode_body(response.body());
+    let millis = elapsed.as_secs_f64() * 1000.0;
+
+    HarEntry {
+        started_date_time: chrono::DateTime::<chrono::Utc>::from(started)
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        time: millis,
+        request: HarRequest {
+            method: request.method().to_string(),
+            url: request.uri().to_string(),
+            http_version: "HTTP/1.1".to_string(),
+            cookies: Vec::new(),
+            headers: name_values(request.headers()),
+            query_string,
+            post_data,
+            headers_size: -1,
+            body_size: request.body().len() as i64,
+        },
+        response: HarResponse {
+            status: response.status().as_u16(),
+            status_text: response.status().canonical_reason().unwrap_or("").to_string(),
+            http_version: "HTTP/1.1".to_string(),
+            cookies: Vec::new(),
+            headers: name_values(response.headers()),
+            content: HarContent {
+                size: response.body().len() as i64,
+                mime_type: header_value(response.headers(), header::CONTENT_TYPE),
+                text: Some(text),
+                encoding,
+            },
+            redirect_url: header_value(response.headers(), header::LOCATION),
+            headers_size: -1,
+            body_size: response.body().len() as i64,
+        },
+        cache: serde_json::json!({}),
+        timings: serde_json::json!({ "send": 0, "wait": millis, "receive": 0 }),
+    }
+}
+
+fn to_response(recorded: &HarResponse) -> Option<Response<Vec<u8>>> {
+    let body = match (&recorded.content.text, recorded.content.encoding.as_deref()) {
+        (Some(text), Some("base64")) => base64::engine::general_purpose::STANDARD.decode(text).ok()?,
+        (Some(text), _) => text.clone().into_bytes(),
+        (None, _) => Vec::new(),
+    };
+
+    let mut builder = Response::builder().status(recorded.status);
+    for entry in &recorded.headers {
+        let name = entry.name.to_ascii_lowercase();
+        if name == "content-length" || name == "transfer-encoding" {
+            continue;
+        }
+        builder = builder.header(entry.name.as_str(), entry.value.as_str());
+    }
+    builder.body(body).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, uri: &str, body: &str) -> Request<Vec<u8>> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(body.as_bytes().to_vec())
+            .unwrap()
+    }
+
+    fn response(status: u16, body: &str) -> Response<Vec<u8>> {
+        Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.as_bytes().to_vec())
+            .unwrap()
+    }
+
+    fn record(session: &HarSession, request: &Request<Vec<u8>>, status: u16, body: &str) {
+        session.record(request, &response(status, body), SystemTime::now(), Duration::from_millis(5));
+    }
+
+    fn replay_body(session: &HarSession, request: &Request<Vec<u8>>) -> Option<String> {
+        session
+            .replay(request)
+            .map(|response| String::from_utf8(response.into_body()).unwrap())
+    }
+
+    fn entries(path: &Path) -> usize {
+        Har::load(path).unwrap().log.entries.len()
+    }
+
+    #[test]
+    fn har_path_accepts_file_names_only() {
+        let dir = Path::new("/data/har");
+        assert_eq!(har_path(dir, "studio", "run1").unwrap(), dir.join("studio").join("run1.har"));
+        assert_eq!(har_path(dir, "studio", "run1.HAR").unwrap(), dir.join("studio").join("run1.HAR"));
+        for name in ["", ".", "..", "../x", "a/b", "a\\b", "/etc/passwd", "C:x", "C:\\x"] {
+            assert!(har_path(dir, "studio", name).is_err(), "{:?}", name);
+        }
+        assert!(har_path(dir, "../other", "run1").is_err());
+    }
+
+    #[test]
+    fn recorded_traffic_replays() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("studio").join("run.har");
+        let session = HarSession::default();
+        session.start_recording(path.clone()).unwrap();
+        record(&session, &request("GET", "myapp://studio/api/items?b=2&a=1", ""), 200, r#"{"page":1}"#);
+        record(&session, &request("GET", "myapp://studio/api/items?b=2&a=1", ""), 200, r#"{"page":2}"#);
+        record(&session, &request("POST", "myapp://studio/api/items", r#"{"name":"a"}"#), 201, r#"{"id":1}"#);
+        record(&session, &request("POST", "myapp://studio/api/items", r#"{"name":"b"}"#), 201, r#"{"id":2}"#);
+        assert_eq!(session.stop_recording().unwrap(), Some(path.clone()));
+
+        assert_eq!(session.set_replay(Some(path.clone()), HarMatch::default()).unwrap(), 4);
+        assert_eq!(session.status(), ("replaying", Some(path.clone())));
+        // 查询参数顺序不影响匹配；依次使用尚未回放的记录，全部用过后重复最后一条
+        let list = request("GET", "myapp://studio/api/items?a=1&b=2", "");
+        assert_eq!(replay_body(&session, &list).unwrap(), r#"{"page":1}"#);
+        assert_eq!(replay_body(&session, &list).unwrap(), r#"{"page":2}"#);
+        assert_eq!(replay_body(&session, &list).unwrap(), r#"{"page":2}"#);
+        assert!(replay_body(&session, &request("GET", "myapp://studio/api/items?a=2", "")).is_none());
+        assert!(replay_body(&session, &request("DELETE", "myapp://studio/api/items", "")).is_none());
+
+        // 按请求体匹配
+        let matching = HarMatch {
+            body: true,
+            ..HarMatch::default()
+        };
+        session.set_replay(Some(path.clone()), matching).unwrap();
+        let create_b = request("POST", "myapp://studio/api/items", r#"{"name":"b"}"#);
+        let response = session.replay(&create_b).unwrap();
+        assert_eq!(response.status(), 201);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.into_body(), br#"{"id":2}"#);
+        assert!(replay_body(&session, &request("POST", "myapp://studio/api/items", r#"{"name":"c"}"#)).is_none());
+
+        session.set_replay(None, HarMatch::default()).unwrap();
+        assert!(session.replay(&create_b).is_none());
+    }
+
+    #[test]
+    fn recording_is_buffered_until_flush_or_stop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.har");
+        let session = HarSession::default();
+        session.start_recording(path.clone()).unwrap();
+        let items = request("GET", "myapp://studio/api/items", "");
+
+        record(&session, &items, 200, "[]");
+        assert_eq!(entries(&path), 0);
+        session.flush().unwrap();
+        assert_eq!(entries(&path), 1);
+
+        record(&session, &items, 200, "[]");
+        assert_eq!(session.stop_recording().unwrap(), Some(path.clone()));
+        assert_eq!(entries(&path), 2);
+        assert_eq!(session.stop_recording().unwrap(), None);
+
+        // 会话释放时写入剩余的记录
+        let session = HarSession::default();
+        session.start_recording(path.clone()).unwrap();
+        record(&session, &items, 200, "[]");
+        drop(session);
+        assert_eq!(entries(&path), 1);
+    }
+
+    #[test]
+    fn replaying_the_recording_file_flushes_it_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.har");
+        let session = HarSession::default();
+        session.start_recording(path.clone()).unwrap();
+        record(&session, &request("GET", "myapp://studio/api/me", ""), 200, r#"{"id":1}"#);
+
+        assert_eq!(session.set_replay(Some(path.clone()), HarMatch::default()).unwrap(), 1);
+        assert_eq!(session.status().0, "replaying");
+        assert!(session.replay(&request("GET", "myapp://studio/api/me", "")).is_some());
+    }
+}
@@ -0,0 +1,345 @@
+// HTML 注入管线：按顺序把样式、脚本、meta、base 等片段插入到项目页面的固定位置
+// 使用流式 HTML 重写器定位 <head>/<body>，不依赖标签大小写，缺少闭合标签时也能插入
+use std::cell::Cell;
+use std::rc::Rc;
+
+use lol_html::html_content::ContentType;
+use lol_html::{element, end, end_tag, HtmlRewriter, Settings};
+
+use super::overlay::OverlayConfig;
+
+// 注入位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectionPoint {
+    // <head> 开头（base、meta 需要尽早生效）
+    HeadStart,
+    // </head> 之前
+    HeadEnd,
+    // </body> 之前
+    BodyEnd,
+}
+
+// 注入的内容
+#[derive(Debug, Clone)]
+pub enum InjectorKind {
+    // 内联样式（CSS 文本）
+    Style(String),
+    // 内联脚本（JS 文本）
+    Script(String),
+    // <meta name=... content=...>
+    Meta { name: String, content: String },
+    // <base href=...>
+    BaseHref(String),
+}
+
+impl InjectorKind {
+    fn to_html(&self) -> String {
+        match self {
+            InjectorKind::Style(css) => format!("<style>\n{}</style>\n", css),
+            InjectorKind::Script(js) => format!("<script>\n{}</script>\n", js),
+            InjectorKind::Meta { name, content } => format!(
+                "<meta name=\"{}\" content=\"{}\">\n",
+                escape_attr(name),
+                escape_attr(content)
+            ),
+            InjectorKind::BaseHref(href) => format!("<base href=\"{}\">\n", escape_attr(href)),
+        }
+    }
+}
+
+// 单个注入器，name 用于按项目开启 / 关闭
+#[derive(Debug, Clone)]
+pub struct Injector {
+    pub name: String,
+    pub point: InjectionPoint,
+    pub kind: InjectorKind,
+    pub enabled: bool,
+}
+
+impl Injector {
+    pub fn new(name: impl Into<String>, point: InjectionPoint, kind: InjectorKind) -> Self {
+        Self {
+            name: name.into(),
+            point,
+            kind,
+            enabled: true,
+        }
+    }
+
+    // 样式默认插入到 </head> 之前
+    pub fn style(name: impl Into<String>, css: impl Into<String>) -> Self {
+        Self::new(name, InjectionPoint::HeadEnd, InjectorKind::Style(css.into()))
+    }
+
+    // 脚本默认插入到 </body> 之前
+    pub fn script(name: impl Into<String>, js: impl Into<String>) -> Self {
+        Self::new(name, InjectionPoint::BodyEnd, InjectorKind::Script(js.into()))
+    }
+
+    pub fn meta(name: impl Into<String>, meta_name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self::new(
+            name,
+            InjectionPoint::HeadStart,
+            InjectorKind::Meta {
+                name: meta_name.into(),
+                content: content.into(),
+            },
+        )
+    }
+
+    pub fn base_href(name: impl Into<String>, href: impl Into<String>) -> Self {
+        Self::new(name, InjectionPoint::HeadStart, InjectorKind::BaseHref(href.into()))
+    }
+
+    // 修改注入位置
+    pub fn at(mut self, point: InjectionPoint) -> Self {
+        self.point = point;
+        self
+    }
+}
+
+// 有序的注入器列表，同一位置的片段按列表顺序插入
+#[derive(Debug, Clone, Default)]
+pub struct InjectorPipeline {
+    injectors: Vec<Injector>,
+}
+
+impl InjectorPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 内置管线：默认配置的返回首页浮层
+    pub fn builtin() -> Self {
+        Self::new().with_overlay(&OverlayConfig::default())
+    }
+
+    // 按浮层配置生成返回首页按钮的样式和脚本，替换已有的同名注入器
+    // 浮层关闭或注入器已被单独关闭时保持关闭
+    pub fn with_overlay(self, overlay: &OverlayConfig) -> Self {
+        let mut pipeline = self;
+        for mut injector in overlay.injectors() {
+            let was_enabled = pipeline
+                .injectors
+                .iter()
+                .find(|i| i.name == injector.name)
+                .is_none_or(|i| i.enabled);
+            injector.enabled = overlay.enabled && was_enabled;
+            pipeline = pipeline.with(injector);
+        }
+        pipeline
+    }
+
+    // 追加注入器，同名注入器会被替换（保留原有顺序）
+    pub fn with(mut self, injector: Injector) -> Self {
+        match self.injectors.iter_mut().find(|i| i.name == injector.name) {
+            Some(existing) => *existing = injector,
+            None => self.injectors.push(injector),
+        }
+        self
+    }
+
+    // 开启 / 关闭指定注入器，返回是否找到
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self.injectors.iter_mut().find(|i| i.name == name) {
+            Some(injector) => {
+                injector.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn injectors(&self) -> &[Injector] {
+        &self.injectors
+    }
+
+    // 某个位置需要插入的 HTML
+    fn fragment(&self, point: InjectionPoint) -> String {
+        self.injectors
+            .iter()
+            .filter(|i| i.enabled && i.point == point)
+            .map(|i| i.kind.to_html())
+            .collect()
+    }
+
+    // 对 HTML 执行注入；每个位置只插入一次，重写失败时原样返回
+    //   HeadStart / HeadEnd：第一个 <head> 的开头 / 结尾；没有 </head> 时插到 <body> 之前
+    //   BodyEnd：第一个 </body> 之前
+    //   仍未插入的片段（缺少对应标签）追加到文档末尾
+    pub fn apply(&self, content: Vec<u8>) -> Vec<u8> {
+        if !self.injectors.iter().any(|i| i.enabled) {
+            return content;
+        }
+
+        let head_start = self.fragment(InjectionPoint::HeadStart);
+        let head_end = self.fragment(InjectionPoint::HeadEnd);
+        let body_end = self.fragment(InjectionPoint::BodyEnd);
+
+        // 各位置是否已插入；闭合标签的回调需要 'static，因此用 Rc 共享
+        let head_start_done = Rc::new(Cell::new(false));
+        let head_end_done = Rc::new(Cell::new(false));
+        let body_seen = Cell::new(false);
+        let body_end_done = Rc::new(Cell::new(false));
+
+        let mut output = Vec::with_capacity(content.len() + head_start.len() + head_end.len() + body_end.len());
+        let mut rewriter = HtmlRewriter::new(
+            Settings {
+                element_content_handlers: vec![
+                    element!("head", |el| {
+                        if head_start_done.replace(true) {
+                            return Ok(());
+                        }
+                        el.prepend(&head_start, ContentType::Html);
+                        let done = head_end_done.clone();
+                        let html = head_end.clone();
+                        el.on_end_tag(end_tag!(move |end| {
+                            if !done.replace(true) {
+                                end.before(&html, ContentType::Html);
+                            }
+                            Ok(())
+                        }))
+                    }),
+                    element!("body", |el| {
+                        if body_seen.replace(true) {
+                            return Ok(());
+                        }
+                        // 没有 <head> 或 </head>：浏览器会把 <body> 前的 style/meta 归入 head
+                        if !head_start_done.replace(true) {
+                            el.before(&head_start, ContentType::Html);
+                        }
+                        if !head_end_done.replace(true) {
+                            el.before(&head_end, ContentType::Html);
+                        }
+                        let done = body_end_done.clone();
+                        let html = body_end.clone();
+                        el.on_end_tag(end_tag!(move |end| {
+                            if !done.replace(true) {
+                                end.before(&html, ContentType::Html);
+                            }
+                            Ok(())
+                        }))
+                    }),
+                ],
+                document_content_handlers: vec![end!(|end| {
+                    for (done, html) in [
+                        (&head_start_done, &head_start),
+                        (&head_end_done, &head_end),
+                        (&body_end_done, &body_end),
+                    ] {
+                        if !done.replace(true) {
+                            end.append(html, ContentType::Html);
+                        }
+                    }
+                    Ok(())
+                })],
+                ..Settings::new()
+            },
+            |chunk: &[u8]| output.extend_from_slice(chunk),
+        );
+
+        if let Err(e) = rewriter.write(&content).and_then(|_| rewriter.end()) {
+            println!("⚠️ HTML 注入失败，返回原始内容: {}", e);
+            return content;
+        }
+        output
+    }
+}
+
+pub(crate) fn escape_attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLE: &str = "<style>\n.x{}</style>\n";
+    const SCRIPT: &str = "<script>\nrun()</script>\n";
+    const META: &str = "<meta name=\"shell\" content=\"1\">\n";
+
+    fn pipeline() -> InjectorPipeline {
+        InjectorPipeline::new()
+            .with(Injector::meta("meta", "shell", "1"))
+            .with(Injector::style("style", ".x{}"))
+            .with(Injector::script("script", "run()"))
+    }
+
+    fn apply(pipeline: &InjectorPipeline, html: &str) -> String {
+        String::from_utf8(pipeline.apply(html.as_bytes().to_vec())).unwrap()
+    }
+
+    #[test]
+    fn injects_at_head_and_body() {
+        let html = apply(&pipeline(), "<html><head><title>t</title></head><body><p>hi</p></body></html>");
+        assert_eq!(
+            html,
+            format!("<html><head>{META}<title>t</title>{STYLE}</head><body><p>hi</p>{SCRIPT}</body></html>")
+        );
+    }
+
+    #[test]
+    fn missing_head_end_falls_back_to_body() {
+        let html = apply(&pipeline(), "<html><head><title>t</title><body><p>hi</p></body></html>");
+        assert_eq!(
+            html,
+            format!("<html><head>{META}<title>t</title>{STYLE}<body><p>hi</p>{SCRIPT}</body></html>")
+        );
+
+        // 没有 head 和 body 时追加到文档末尾
+        let html = apply(&pipeline(), "<p>hi</p>");
+        assert_eq!(html, format!("<p>hi</p>{META}{STYLE}{SCRIPT}"));
+    }
+
+    #[test]
+    fn uppercase_tags_are_matched() {
+        let html = apply(&pipeline(), "<HTML><HEAD><TITLE>t</TITLE></HEAD><BODY>hi</BODY></HTML>");
+        assert_eq!(
+            html,
+            format!("<HTML><HEAD>{META}<TITLE>t</TITLE>{STYLE}</HEAD><BODY>hi{SCRIPT}</BODY></HTML>")
+        );
+    }
+
+    #[test]
+    fn multiple_head_end_tags_inject_once() {
+        let html = apply(&pipeline(), "<head></head><head></head><body></body><body></body>");
+        assert_eq!(html.matches(STYLE).count(), 1, "{}", html);
+        assert_eq!(html.matches(META).count(), 1, "{}", html);
+        assert_eq!(html.matches(SCRIPT).count(), 1, "{}", html);
+        assert!(html.starts_with(&format!("<head>{META}{STYLE}</head><head></head>")), "{}", html);
+    }
+
+    #[test]
+    fn disabled_injectors_are_skipped() {
+        let mut pipeline = pipeline();
+        assert!(pipeline.set_enabled("style", false));
+        assert!(!pipeline.set_enabled("missing", false));
+        let html = apply(&pipeline, "<head></head><body></body>");
+        assert_eq!(html, format!("<head>{META}</head><body>{SCRIPT}</body>"));
+
+        // 全部关闭时原样返回
+        pipeline.set_enabled("meta", false);
+        pipeline.set_enabled("script", false);
+        let original = "<head></head><body></body>";
+        assert_eq!(apply(&pipeline, original), original);
+    }
+
+    #[test]
+    fn project_injector_switches_survive_overlay() {
+        let mut pipeline = InjectorPipeline::builtin();
+        let names: Vec<String> = pipeline.injectors().iter().map(|i| i.name.clone()).collect();
+        assert!(!names.is_empty());
+        for name in &names {
+            assert!(pipeline.set_enabled(name, false));
+        }
+        // 项目单独关闭的注入器在重新生成浮层后仍保持关闭
+        let pipeline = pipeline.with_overlay(&OverlayConfig::default());
+        assert!(pipeline.injectors().iter().all(|i| !i.enabled));
+        let original = "<head></head><body></body>";
+        assert_eq!(apply(&pipeline, original), original);
+    }
+}
@@ -0,0 +1,187 @@
+// 文件完整性校验：按签名清单中的 SHA-256 在读取时校验文件，拒绝被篡改或清单外的文件
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+
+use super::source::{normalize_path, AssetError, AssetMetadata, AssetSource};
+
+// 相对路径（以 "/" 分隔）到 SHA-256（十六进制小写）的映射
+#[derive(Debug, Clone, Default)]
+pub struct FileHashes {
+    hashes: Arc<HashMap<String, String>>,
+}
+
+impl FileHashes {
+    pub fn new(hashes: HashMap<String, String>) -> Self {
+        Self {
+            hashes: Arc::new(
+                hashes
+                    .into_iter()
+                    .map(|(path, hash)| (path, hash.to_ascii_lowercase()))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    // 按规范化后的相对路径查找，"a/./b.js" 与 "a/b.js" 等价
+    pub fn get(&self, path: &str) -> Option<&str> {
+        let key = normalize_path(path)?;
+        self.hashes.get(&key).map(String::as_str)
+    }
+
+    // 内容是否与清单一致
+    pub fn matches(&self, path: &str, content: &[u8]) -> Result<(), AssetError> {
+        let expected = self
+            .get(path)
+            .ok_or_else(|| AssetError::Tampered(format!("文件不在签名清单中: {}", path)))?;
+        let actual = sha256_hex(content);
+        if actual != expected {
+            return Err(AssetError::Tampered(format!(
+                "文件内容与签名清单不一致: {}（期望 {}，实际 {}）",
+                path, expected, actual
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub fn sha256_hex(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+// 包装另一个资源来源，首次读取文件时校验哈希，校验结果按文件大小和修改时间缓存
+pub struct VerifiedAssetSource<S> {
+    inner: S,
+    hashes: FileHashes,
+    verified: Mutex<HashMap<String, (u64, Option<SystemTime>)>>,
+}
+
+impl<S: AssetSource> VerifiedAssetSource<S> {
+    pub fn new(inner: S, hashes: FileHashes) -> Self {
+        Self {
+            inner,
+            hashes,
+            verified: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn stamp(metadata: &AssetMetadata) -> (u64, Option<SystemTime>) {
+        (metadata.len, metadata.modified)
+    }
+
+    fn is_verified(&self, path: &str, metadata: &AssetMetadata) -> bool {
+        self.verified.lock().unwrap().get(path) == Some(&Self::stamp(metadata))
+    }
+
+    // 读取完整内容并校验，失败时清除缓存的校验结果
+    fn read_verified(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let metadata = self.inner.metadata(path)?;
+        let content = self.inner.read(path)?;
+        if let Err(e) = self.hashes.matches(path, &content) {
+            self.verified.lock().unwrap().remove(path);
+            return Err(e);
+        }
+        self.verified
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), Self::stamp(&metadata));
+        Ok(content)
+    }
+}
+
+impl<S: AssetSource> AssetSource for VerifiedAssetSource<S> {
+    // 清单外的文件即使存在也拒绝，不存在的文件照常返回 NotFound（SPA 回退依赖它）
+    fn metadata(&self, path: &str) -> Result<AssetMetadata, AssetError> {
+        let metadata = self.inner.metadata(path)?;
+        if self.hashes.get(path).is_none() {
+            return Err(AssetError::Tampered(format!("文件不在签名清单中: {}", path)));
+        }
+        Ok(metadata)
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        self.read_verified(path)
+    }
+
+    // 分段读取前先完整校验一次文件，之后未变化的文件直接分段读取
+    fn read_range(&self, path: &str, start: u64, len: u64) -> Result<Vec<u8>, AssetError> {
+        let metadata = self.inner.metadata(path)?;
+        if self.is_verified(path, &metadata) {
+            return self.inner.read_range(path, start, len);
+        }
+        let content = self.read_verified(path)?;
+        let start = (start as usize).min(content.len());
+        let end = start.saturating_add(len as usize).min(content.len());
+        Ok(content[start..end].to_vec())
+    }
+}
+
+// 策略要求签名、但项目没有有效签名时使用：拒绝读取任何文件
+pub struct UnsignedAssetSource;
+
+impl AssetSource for UnsignedAssetSource {
+    fn metadata(&self, path: &str) -> Result<AssetMetadata, AssetError> {
+        Err(AssetError::Tampered(format!("项目包没有签名，拒绝读取: {}", path)))
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        Err(AssetError::Tampered(format!("项目包没有签名，拒绝读取: {}", path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::FsAssetSource;
+
+    fn verified(dir: &std::path::Path) -> VerifiedAssetSource<FsAssetSource> {
+        std::fs::write(dir.join("app.js"), "console.log(1)").unwrap();
+        std::fs::write(dir.join("extra.js"), "").unwrap();
+        let hashes = HashMap::from([
+            ("app.js".to_string(), sha256_hex(b"console.log(1)").to_ascii_uppercase()),
+            ("missing.js".to_string(), sha256_hex(b"")),
+        ]);
+        VerifiedAssetSource::new(FsAssetSource::new(dir), FileHashes::new(hashes))
+    }
+
+    #[test]
+    fn intact_files_are_served() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = verified(dir.path());
+        assert_eq!(source.read("app.js").unwrap(), b"console.log(1)");
+        assert_eq!(source.read("./app.js").unwrap(), b"console.log(1)");
+        assert_eq!(source.read_range("app.js", 8, 3).unwrap(), b"log");
+        assert!(matches!(source.metadata("missing.js"), Err(AssetError::NotFound)));
+    }
+
+    #[test]
+    fn tampered_file_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = verified(dir.path());
+        assert!(source.read("app.js").is_ok());
+
+        std::fs::write(dir.path().join("app.js"), "alert('tampered')").unwrap();
+        assert!(matches!(source.read("app.js"), Err(AssetError::Tampered(_))));
+        // 分段读取不能绕过校验
+        assert!(matches!(source.read_range("app.js", 0, 5), Err(AssetError::Tampered(_))));
+    }
+
+    #[test]
+    fn files_outside_manifest_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = verified(dir.path());
+        assert!(matches!(source.metadata("extra.js"), Err(AssetError::Tampered(_))));
+        assert!(matches!(source.read("extra.js"), Err(AssetError::Tampered(_))));
+        assert!(matches!(UnsignedAssetSource.read("app.js"), Err(AssetError::Tampered(_))));
+    }
+}
@@ -0,0 +1,157 @@
+// 按扩展名确定 Content-Type：内置 MIME 表 + 每个项目的自定义扩展名覆盖
+use std::collections::HashMap;
+
+// 未知扩展名的默认类型
+pub const DEFAULT_MIME: &str = "application/octet-stream";
+
+// 扩展名（小写、不带点）到 MIME 类型
+const MIME_TABLE: &[(&str, &str)] = &[
+    // 文档与脚本
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("css", "text/css"),
+    ("js", "text/javascript"),
+    ("mjs", "text/javascript"),
+    ("cjs", "text/javascript"),
+    ("json", "application/json"),
+    ("map", "application/json"),
+    ("webmanifest", "application/manifest+json"),
+    ("xml", "application/xml"),
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("csv", "text/csv"),
+    ("wasm", "application/wasm"),
+    ("pdf", "application/pdf"),
+    // 图片
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+    ("svg", "image/svg+xml"),
+    ("ico", "image/x-icon"),
+    ("bmp", "image/bmp"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    // 字体
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("eot", "application/vnd.ms-fontobject"),
+    // 音视频
+    ("mp4", "video/mp4"),
+    ("m4v", "video/mp4"),
+    ("webm", "video/webm"),
+    ("ogv", "video/ogg"),
+    ("mov", "video/quicktime"),
+    ("mp3", "audio/mpeg"),
+    ("m4a", "audio/mp4"),
+    ("aac", "audio/aac"),
+    ("wav", "audio/wav"),
+    ("ogg", "audio/ogg"),
+    ("oga", "audio/ogg"),
+    ("opus", "audio/opus"),
+    ("flac", "audio/flac"),
+    // 3D 模型与压缩包
+    ("glb", "model/gltf-binary"),
+    ("gltf", "model/gltf+json"),
+    ("zip", "application/zip"),
+    ("gz", "application/gzip"),
+];
+
+// 内置表中的 MIME 类型（不含参数）
+pub fn lookup(extension: &str) -> Option<&'static str> {
+    let extension = extension.to_ascii_lowercase();
+    MIME_TABLE
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, mime)| *mime)
+}
+
+// 去掉参数后的 MIME 类型，例如 "text/html; charset=utf-8" -> "text/html"
+pub fn essence(mime_type: &str) -> &str {
+    mime_type.split(';').next().unwrap_or(mime_type).trim()
+}
+
+// 文本类型需要声明字符集
+fn needs_charset(essence: &str) -> bool {
+    essence.starts_with("text/")
+        || matches!(
+            essence,
+            "application/json" | "application/manifest+json" | "application/xml" | "image/svg+xml"
+        )
+}
+
+// 根据路径确定 Content-Type，overrides 为项目自定义的扩展名映射（优先于内置表）
+pub fn mime_type(path: &str, overrides: &HashMap<String, String>) -> String {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let extension = file_name
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase());
+
+    let mime_type = extension
+        .as_deref()
+        .and_then(|extension| {
+            overrides
+                .get(extension)
+                .map(String::as_str)
+                .or_else(|| lookup(extension))
+        })
+        .unwrap_or(DEFAULT_MIME);
+
+    // 已带参数（例如自定义的 charset）则原样使用
+    if mime_type.contains(';') || !needs_charset(mime_type) {
+        mime_type.to_string()
+    } else {
+        format!("{}; charset=utf-8", mime_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mime(path: &str) -> String {
+        mime_type(path, &HashMap::new())
+    }
+
+    #[test]
+    fn modern_web_types() {
+        assert_eq!(mime("pkg/app_bg.wasm"), "application/wasm");
+        assert_eq!(mime("assets/index-B3xK_9aZ.mjs"), "text/javascript; charset=utf-8");
+        assert_eq!(mime("manifest.webmanifest"), "application/manifest+json; charset=utf-8");
+        assert_eq!(mime("assets/app.js.map"), "application/json; charset=utf-8");
+        assert_eq!(mime("icons/logo.svg"), "image/svg+xml; charset=utf-8");
+        assert_eq!(mime("fonts/inter.woff2"), "font/woff2");
+    }
+
+    #[test]
+    fn extensions_are_case_insensitive() {
+        assert_eq!(mime("INDEX.HTML"), "text/html; charset=utf-8");
+        assert_eq!(mime("photos/IMG_0001.JPG"), "image/jpeg");
+        assert_eq!(mime("pkg/App.WASM"), "application/wasm");
+        assert_eq!(lookup("PNG"), Some("image/png"));
+    }
+
+    #[test]
+    fn unknown_extensions_fall_back() {
+        for path in ["data.xyz", "LICENSE", "v1.2/README", "archive.tar.bz2", "trailing."] {
+            assert_eq!(mime(path), DEFAULT_MIME, "{}", path);
+        }
+        assert_eq!(lookup("xyz"), None);
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        let overrides = HashMap::from([
+            ("glsl".to_string(), "text/x-glsl".to_string()),
+            ("js".to_string(), "application/javascript; charset=gbk".to_string()),
+        ]);
+        assert_eq!(mime_type("shaders/light.GLSL", &overrides), "text/x-glsl; charset=utf-8");
+        assert_eq!(mime_type("legacy.js", &overrides), "application/javascript; charset=gbk");
+        assert_eq!(mime_type("app.css", &overrides), "text/css; charset=utf-8");
+        assert_eq!(essence("text/html; charset=utf-8"), "text/html");
+    }
+}
@@ -0,0 +1,373 @@
+// 本地模拟后端：离线时用预先定义的路由响应项目发出的 api/、session/、ai/ 请求
+//
+// 目录结构：
+//   {mock_dir}/routes/*.json   路由定义，每个文件是一个路由数组
+//   {mock_dir}/...             响应体文件，路由中的 file 相对于 mock_dir
+//
+// 路由示例：
+//   { "method": "GET", "path": "/api/users/:id", "status": 200,
+//     "headers": { "X-Mock": "1" }, "file": "responses/user.json" }
+//
+// path 中 ":name" 匹配一段并作为参数，末尾的 "*" 匹配剩余所有段。
+// 响应体和响应头支持 {{params.name}}、{{query.name}}、{{method}}、{{path}}、{{now}} 模板变量。
+// JSON 响应体中替换的值按 JSON 字符串转义，模板变量应写在字符串字面量内，例如 "id": "{{params.id}}"。
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use tauri::http::{header, Request, Response};
+
+use super::{mime, AssetSource, FileHashes, FsAssetSource, VerifiedAssetSource};
+
+// 单条路由定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct MockRoute {
+    // HTTP 方法，"*" 匹配任意方法
+    #[serde(default = "default_method")]
+    pub method: String,
+    // 路径模式，例如 /api/users/:id
+    pub path: String,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    // 响应体文件，相对于 mock 目录
+    #[serde(default)]
+    pub file: Option<String>,
+    // 内联响应体；字符串原样返回，其他 JSON 值序列化后返回
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+// 一个项目的模拟后端
+pub struct MockBackend {
+    routes: Vec<MockRoute>,
+    files: Box<dyn AssetSource>,
+}
+
+impl MockBackend {
+    // 从 mock 目录加载所有路由定义
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        Self::load_verified(dir, None)
+    }
+
+    // integrity 为签名清单中 mock 目录下文件的哈希：路由文件加载时校验，响应体文件读取时校验
+    pub fn load_verified(dir: &Path, integrity: Option<FileHashes>) -> Result<Self, String> {
+        let routes_dir = dir.join("routes");
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(&routes_dir)
+            .map_err(|e| format!("读取路由目录失败 {:?}: {}", routes_dir, e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("json"))
+            .collect();
+        // 按文件名排序，保证匹配顺序稳定
+        entries.sort();
+
+        let mut routes = Vec::new();
+        for path in entries {
+            let content = std::fs::read(&path).map_err(|e| format!("读取路由文件失败 {:?}: {}", path, e))?;
+            if let Some(integrity) = &integrity {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                integrity
+                    .matches(&format!("routes/{}", name), &content)
+                    .map_err(|e| format!("路由文件校验失败 {:?}: {}", path, e))?;
+            }
+            let mut file_routes: Vec<MockRoute> = serde_json::from_slice(&content)
+                .map_err(|e| format!("解析路由文件失败 {:?}: {}", path, e))?;
+            routes.append(&mut file_routes);
+        }
+
+        let files: Box<dyn AssetSource> = match integrity {
+            Some(integrity) => Box::new(VerifiedAssetSource::new(FsAssetSource::new(dir), integrity)),
+            None => Box::new(FsAssetSource::new(dir)),
+        };
+        Ok(Self { routes, files })
+    }
+
+    pub fn new(dir: &Path, routes: Vec<MockRoute>) -> Self {
+        Self {
+            routes,
+            files: Box::new(FsAssetSource::new(dir)),
+        }
+    }
+
+    pub fn routes(&self) -> &[MockRoute] {
+        &self.routes
+    }
+
+    // 按定义顺序查找第一条匹配的路由并生成响应，没有匹配时返回 None
+    // path 为项目内相对路径（已解码，不以 "/" 开头）
+    pub fn respond(&self, request: &Request<Vec<u8>>, path: &str) -> Option<Response<Vec<u8>>> {
+        let method = request.method().as_str();
+        let path = format!("/{}", path);
+        let (route, params) = self.routes.iter().find_map(|route| {
+            if route.method != "*" && !route.method.eq_ignore_ascii_case(method) {
+                return None;
+            }
+            match_path(&route.path, &path).map(|params| (route, params))
+        })?;
+        println!("🎭 模拟后端命中: {} {} -> {}", method, path, route.path);
+
+        let context = TemplateContext {
+            method,
+            path: &path,
+            params: &params,
+            query: parse_query(request.uri().query().unwrap_or("")),
+        };
+
+        let (body, content_type) = match (&route.file, &route.body) {
+            (Some(file), _) => match self.files.read(file) {
+                Ok(content) => (content, mime::mime_type(file, &HashMap::new())),
+                Err(e) => {
+                    println!("❌ 模拟响应文件读取失败 {}: {}", file, e);
+                    return Some(
+                        Response::builder()
+                            .status(500)
+                            .header(header::CONTENT_TYPE, "application/json")
+                            .body(
+                                serde_json::json!({ "error": format!("mock file {}: {}", file, e) })
+                                    .to_string()
+                                    .into_bytes(),
+                            )
+                            .unwrap(),
+                    );
+                }
+            },
+            (None, Some(serde_json::Value::String(text))) => {
+                (text.clone().into_bytes(), "text/plain; charset=utf-8".to_string())
+            }
+            (None, Some(value)) => (value.to_string().into_bytes(), "application/json".to_string()),
+            (None, None) => (Vec::new(), "application/json".to_string()),
+        };
+
+        // 文本内容才做模板替换；JSON 内容转义替换的值，避免请求参数破坏或注入 JSON 结构
+        let json = is_json(&content_type);
+        let body = match String::from_utf8(body) {
+            Ok(text) => context.render(&text, json).into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+
+        let mut builder = Response::builder()
+            .status(route.status)
+            .header(header::CONTENT_TYPE, content_type)
+            .header(header::CACHE_CONTROL, "no-store");
+        for (name, value) in &route.headers {
+            builder = builder.header(name.as_str(), context.render(value, false));
+        }
+        match builder.body(body) {
+            Ok(response) => Some(response),
+            Err(e) => {
+                println!("❌ 模拟响应构造失败 {}: {}", route.path, e);
+                None
+            }
+        }
+    }
+}
+
+// 匹配路径模式，成功时返回路径参数
+fn match_path(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
+    let pattern_segments: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path_segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let mut params = HashMap::new();
+
+    for (index, segment) in pattern_segments.iter().enumerate() {
+        if *segment == "*" && index == pattern_segments.len() - 1 {
+            params.insert("*".to_string(), path_segments.get(index..)?.join("/"));
+            return Some(params);
+        }
+        let value = path_segments.get(index)?;
+        if let Some(name) = segment.strip_prefix(':') {
+            params.insert(name.to_string(), value.to_string());
+        } else if segment != value {
+            return None;
+        }
+    }
+
+    (pattern_segments.len() == path_segments.len()).then_some(params)
+}
+
+fn is_json(content_type: &str) -> bool {
+    let essence = mime::essence(content_type);
+    essence == "application/json" || essence.ends_with("+json")
+}
+
+// 解析查询字符串
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |s: &str| {
+                let s = s.replace('+', " ");
+                urlencoding::decode(&s).map(|d| d.into_owned()).unwrap_or(s)
+            };
+            (decode(name), decode(value))
+        })
+        .collect()
+}
+
+// 模板变量
+struct TemplateContext<'a> {
+    method: &'a str,
+    path: &'a str,
+    params: &'a HashMap<String, String>,
+    query: HashMap<String, String>,
+}
+
+impl TemplateContext<'_> {
+    // 替换 {{...}} 变量，未知变量替换为空字符串；json 为 true 时按 JSON 字符串内容转义
+    fn render(&self, template: &str, json: bool) -> String {
+        let mut output = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after.find("}}") {
+                Some(end) => {
+                    let value = self.lookup(after[..end].trim());
+                    if json {
+                        output.push_str(&escape_json(&value));
+                    } else {
+                        output.push_str(&value);
+                    }
+                    rest = &after[end + 2..];
+                }
+                None => {
+                    output.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        output.push_str(rest);
+        output
+    }
+
+    fn lookup(&self, name: &str) -> String {
+        if let Some(param) = name.strip_prefix("params.") {
+            return self.params.get(param).cloned().unwrap_or_default();
+        }
+        if let Some(param) = name.strip_prefix("query.") {
+            return self.query.get(param).cloned().unwrap_or_default();
+        }
+        match name {
+            "method" => self.method.to_string(),
+            "path" => self.path.to_string(),
+            "now" => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis().to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+}
+
+// JSON 字符串转义（不含两侧引号）
+fn escape_json(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(method: &str, path: &str, body: serde_json::Value) -> MockRoute {
+        MockRoute {
+            method: method.to_string(),
+            path: path.to_string(),
+            status: 200,
+            headers: HashMap::new(),
+            file: None,
+            body: Some(body),
+        }
+    }
+
+    fn respond(backend: &MockBackend, method: &str, uri: &str) -> Option<(String, String)> {
+        let request = Request::builder().method(method).uri(uri).body(Vec::new()).unwrap();
+        let path = request.uri().path().trim_start_matches('/').to_string();
+        let response = backend.respond(&request, &path)?;
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+        Some((content_type, String::from_utf8(response.into_body()).unwrap()))
+    }
+
+    #[test]
+    fn match_path_params_and_wildcard() {
+        let params = match_path("/api/users/:id/posts/:post", "/api/users/42/posts/7").unwrap();
+        assert_eq!(params["id"], "42");
+        assert_eq!(params["post"], "7");
+        assert!(match_path("/api/users/:id", "/api/users").is_none());
+        assert!(match_path("/api/users/:id", "/api/users/42/posts").is_none());
+        assert!(match_path("/api/users", "/api/groups").is_none());
+
+        let params = match_path("/api/files/*", "/api/files/a/b/c.txt").unwrap();
+        assert_eq!(params["*"], "a/b/c.txt");
+        // "*" 只在末尾时作为通配符
+        assert!(match_path("/api/*/x", "/api/a/x").is_none());
+        assert!(match_path("/api/files/*", "/api").is_none());
+    }
+
+    #[test]
+    fn first_matching_route_wins() {
+        let backend = MockBackend::new(
+            Path::new("."),
+            vec![
+                route("GET", "/api/users/me", serde_json::json!({ "route": "me" })),
+                route("GET", "/api/users/:id", serde_json::json!({ "route": "id" })),
+                route("*", "/api/*", serde_json::json!({ "route": "any" })),
+            ],
+        );
+        assert!(respond(&backend, "GET", "myapp://p/api/users/me").unwrap().1.contains("\"me\""));
+        assert!(respond(&backend, "GET", "myapp://p/api/users/42").unwrap().1.contains("\"id\""));
+        // 方法不匹配时继续尝试后面的路由
+        assert!(respond(&backend, "POST", "myapp://p/api/users/42").unwrap().1.contains("\"any\""));
+        assert!(respond(&backend, "GET", "myapp://p/session/x").is_none());
+    }
+
+    #[test]
+    fn render_substitutes_variables() {
+        let params = HashMap::from([("id".to_string(), "42".to_string())]);
+        let context = TemplateContext {
+            method: "GET",
+            path: "/api/users/42",
+            params: &params,
+            query: parse_query("name=a+b&tag=%E4%B8%AD"),
+        };
+        assert_eq!(
+            context.render("{{method}} {{ path }} {{params.id}} {{query.name}} {{query.tag}}", false),
+            "GET /api/users/42 42 a b 中"
+        );
+        // 未知变量替换为空，未闭合的保留原样
+        assert_eq!(context.render("[{{params.missing}}{{unknown}}] {{open", false), "[] {{open");
+        assert!(context.render("{{now}}", false).parse::<u128>().is_ok());
+    }
+
+    #[test]
+    fn json_values_are_escaped() {
+        let backend = MockBackend::new(
+            Path::new("."),
+            vec![
+                route("GET", "/api/echo/:id", serde_json::json!({ "id": "{{params.id}}", "q": "{{query.q}}" })),
+                route("GET", "/api/text", serde_json::json!("q={{query.q}}")),
+            ],
+        );
+        let (content_type, body) = respond(&backend, "GET", "myapp://p/api/echo/1?q=%22%2C%22admin%22%3Atrue%7D%5C").unwrap();
+        assert_eq!(content_type, "application/json");
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["id"], "1");
+        assert_eq!(value["q"], r#"","admin":true}\"#);
+        assert!(value.get("admin").is_none());
+
+        // 非 JSON 内容原样替换
+        let (_, body) = respond(&backend, "GET", "myapp://p/api/text?q=%22x%22").unwrap();
+        assert_eq!(body, r#"q="x""#);
+        assert_eq!(escape_json("a\"b\\c\n"), r#"a\"b\\c\n"#);
+    }
+}
@@ -0,0 +1,890 @@
+// myapp:// 自定义协议的解析逻辑
+// 把 http::Request 转换为 http::Response，不依赖 WebView，便于单独测试
+pub mod archive;
+pub mod asset_cache;
+pub mod cache;
+pub mod compress;
+pub mod embedded;
+pub mod har;
+pub mod inject;
+pub mod integrity;
+pub mod mime;
+pub mod mock;
+pub mod overlay;
+pub mod proxy;
+pub mod queue;
+pub mod range;
+pub mod source;
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Instant, SystemTime};
+
+use tauri::http::{header, response, Request, Response, Uri};
+
+pub use archive::{ArchiveFormat, TarAssetSource, ZipAssetSource};
+pub use asset_cache::{AssetCache, AssetCacheStats, CachedAsset};
+pub use cache::CachePolicy;
+pub use compress::CompressionConfig;
+use compress::Encoding;
+pub use embedded::{EmbeddedAssetSource, EmbeddedStore};
+pub use har::{HarMatch, HarSession};
+pub use inject::{InjectionPoint, Injector, InjectorKind, InjectorPipeline};
+pub use integrity::{FileHashes, UnsignedAssetSource, VerifiedAssetSource};
+pub use mock::MockBackend;
+pub use overlay::{OverlayConfig, OverlayCorner, OverlayTheme};
+pub use proxy::{ProxyError, ProxyRule, ReverseProxy};
+pub use queue::{OfflineQueue, QueuedRequest};
+use range::RangeRequest;
+pub use source::{AssetError, AssetMetadata, AssetSource, FsAssetSource};
+
+// 协议名称
+pub const SCHEME: &str = "myapp";
+
+// 默认文档
+pub const DEFAULT_DOCUMENT: &str = "index.html";
+
+// 一个已挂载的项目：项目 ID（即 URL 的 host）对应的资源来源
+pub struct ProjectMount {
+    source: Box<dyn AssetSource>,
+    // 入口文件，目录请求和 SPA 回退都指向它
+    entry: String,
+    // history 模式路由回退：找不到的非资源路径返回入口文件
+    spa_fallback: bool,
+    // Cache-Control 策略
+    cache_policy: CachePolicy,
+    // 预压缩 / 即时压缩配置
+    compression: CompressionConfig,
+    // 自定义扩展名的 MIME 类型（扩展名小写、不带点）
+    mime_overrides: HashMap<String, String>,
+    // 本地模拟后端，响应 API 请求
+    mock: Option<MockBackend>,
+    // 反向代理，把 API 请求转发到上游
+    proxy: Option<ReverseProxy>,
+    // API 流量录制 / 回放；重新挂载同一项目时沿用，录制不会因重新扫描中断
+    har: Arc<HarSession>,
+    // HTML 注入管线
+    injectors: InjectorPipeline,
+}
+
+impl ProjectMount {
+    pub fn new(source: impl AssetSource + 'static) -> Self {
+        Self {
+            source: Box::new(source),
+            entry: DEFAULT_DOCUMENT.to_string(),
+            spa_fallback: false,
+            cache_policy: CachePolicy::default(),
+            compression: CompressionConfig::default(),
+            mime_overrides: HashMap::new(),
+            mock: None,
+            proxy: None,
+            har: Arc::new(HarSession::default()),
+            injectors: InjectorPipeline::builtin(),
+        }
+    }
+
+    // 反向代理（没有配置规则时为 None）
+    pub fn reverse_proxy(&self) -> Option<&ReverseProxy> {
+        self.proxy.as_ref()
+    }
+
+    // API 流量录制 / 回放会话
+    pub fn har(&self) -> &HarSession {
+        &self.har
+    }
+
+    // 设置反向代理规则，没有规则时不启用代理
+    pub fn proxy(mut self, rules: Vec<ProxyRule>) -> Self {
+        self.proxy = if rules.is_empty() {
+            None
+        } else {
+            Some(ReverseProxy::new(rules))
+        };
+        self
+    }
+
+    // 设置 HTML 注入管线，空管线表示不注入
+    pub fn injectors(mut self, injectors: InjectorPipeline) -> Self {
+        self.injectors = injectors;
+        self
+    }
+
+    // 设置本地模拟后端
+    pub fn mock(mut self, mock: MockBackend) -> Self {
+        self.mock = Some(mock);
+        self
+    }
+
+    // 设置自定义扩展名的 MIME 类型，例如 {"lottie": "application/json"}
+    pub fn mime_overrides(mut self, overrides: HashMap<String, String>) -> Self {
+        self.mime_overrides = overrides
+            .into_iter()
+            .map(|(extension, mime_type)| {
+                (extension.trim_start_matches('.').to_ascii_lowercase(), mime_type)
+            })
+            .collect();
+        self
+    }
+
+    // 设置压缩配置
+    pub fn compression(mut self, compression: CompressionConfig) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    // 设置 Cache-Control 策略
+    pub fn cache_policy(mut self, cache_policy: CachePolicy) -> Self {
+        self.cache_policy = cache_policy;
+        self
+    }
+
+    // 设置入口文件（相对于项目根目录）
+    pub fn entry(mut self, entry: impl Into<String>) -> Self {
+        self.entry = entry.into();
+        self
+    }
+
+    // 开启或关闭 SPA 回退
+    pub fn spa_fallback(mut self, enabled: bool) -> Self {
+        self.spa_fallback = enabled;
+        self
+    }
+}
+
+// 协议解析器：URL 解析、项目查找、API 过滤、默认文档、读取文件、MIME、HTML 注入
+// URL 形如 myapp://{project_id}/{path}，host 即项目 ID
+#[derive(Default)]
+pub struct ProtocolResolver {
+    // 运行时可以整体替换（重新扫描、安装项目），因此放在 RwLock 中
+    projects: RwLock<HashMap<String, Arc<ProjectMount>>>,
+    // 离线写队列
+    offline_queue: Option<Arc<OfflineQueue>>,
+    // 所有项目共用的内存资源缓存
+    asset_cache: AssetCache,
+}
+
+impl ProtocolResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 挂载一个项目，同名项目会被替换
+    pub fn mount(&mut self, project_id: impl Into<String>, mount: ProjectMount) {
+        self.projects
+            .get_mut()
+            .unwrap()
+            .insert(project_id.into(), Arc::new(mount));
+    }
+
+    // 用新的挂载表替换全部项目（运行时生效）；同名项目沿用原有的 HAR 会话
+    pub fn replace_projects(&self, mounts: HashMap<String, ProjectMount>) {
+        let mut projects = self.projects.write().unwrap();
+        let replaced = mounts
+            .into_iter()
+            .map(|(project_id, mut mount)| {
+                if let Some(existing) = projects.get(&project_id) {
+                    mount.har = existing.har.clone();
+                }
+                (project_id, Arc::new(mount))
+            })
+            .collect();
+        *projects = replaced;
+        self.asset_cache.clear();
+    }
+
+    // 替换单个项目（如切换版本），沿用原有的 HAR 会话，丢弃该项目的缓存
+    pub fn replace_project(&self, project_id: impl Into<String>, mut mount: ProjectMount) {
+        let project_id = project_id.into();
+        let mut projects = self.projects.write().unwrap();
+        if let Some(existing) = projects.get(&project_id) {
+            mount.har = existing.har.clone();
+        }
+        self.asset_cache.invalidate_project(&project_id);
+        projects.insert(project_id, Arc::new(mount));
+    }
+
+    // 已挂载的项目
+    pub fn project(&self, project_id: &str) -> Option<Arc<ProjectMount>> {
+        self.projects.read().unwrap().get(project_id).cloned()
+    }
+
+    // 已挂载的项目 ID
+    pub fn project_ids(&self) -> Vec<String> {
+        self.projects.read().unwrap().keys().cloned().collect()
+    }
+
+    // 把各项目录制中的 HAR 记录写入文件（定时调用）
+    pub fn flush_har(&self) {
+        let mounts: Vec<Arc<ProjectMount>> = self.projects.read().unwrap().values().cloned().collect();
+        for mount in mounts {
+            if let Err(e) = mount.har.flush() {
+                println!("⚠️ {}", e);
+            }
+        }
+    }
+
+    // 内存资源缓存（统计、调整大小、文件监听触发的失效）
+    pub fn asset_cache(&self) -> &AssetCache {
+        &self.asset_cache
+    }
+
+    // 以构建器方式挂载项目
+    pub fn with_project(mut self, project_id: impl Into<String>, source: impl AssetSource + 'static) -> Self {
+        self.mount(project_id, ProjectMount::new(source));
+        self
+    }
+
+    // 设置离线写队列（所有项目共用）
+    pub fn offline_queue(mut self, queue: Arc<OfflineQueue>) -> Self {
+        self.offline_queue = Some(queue);
+        self
+    }
+
+    // 写请求入队，返回 202；没有配置队列或入队失败时返回 502
+    fn enqueue_offline(&self, project_id: &str, request: &Request<Vec<u8>>, error: String) -> Response<Vec<u8>> {
+        let error = ProxyError::Unreachable(error);
+        println!("❌ {}", error);
+        let queue = match &self.offline_queue {
+            Some(queue) => queue,
+            None => return error.into_response(),
+        };
+        match queue.enqueue(project_id, request) {
+            Ok(queued) => {
+                println!("📥 已加入离线队列: {} {} ({})", queued.method, queued.url, queued.id);
+                Response::builder()
+                    .status(202)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .header("X-Offline-Queued", queued.id.as_str())
+                    .body(serde_json::json!({ "queued": true, "id": queued.id }).to_string().into_bytes())
+                    .unwrap()
+            }
+            Err(e) => {
+                println!("❌ 加入离线队列失败: {}", e);
+                error.into_response()
+            }
+        }
+    }
+
+    // 处理一次协议请求
+    pub fn resolve(&self, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+        let uri = request.uri();
+        println!("🔗 收到自定义协议请求: {}", uri);
+
+        let project_id = match project_id(uri) {
+            Some(project_id) => project_id,
+            None => {
+                println!("❌ 缺少项目 ID: {}", uri);
+                return respond(400, None, "缺少项目 ID".as_bytes().to_vec());
+            }
+        };
+
+        // 未注册的项目一律拒绝
+        let mount = match self.project(project_id) {
+            Some(mount) => mount,
+            None => {
+                println!("❌ 未知项目: {}", project_id);
+                return respond(404, None, format!("未知项目: {}", project_id).into_bytes());
+            }
+        };
+
+        let path = request_path(uri);
+        println!("📂 解析后的路径: {} -> {}", project_id, path);
+
+        // 检查是否是 API 请求（如 session/xxx, ai/xxx 等）
+        // 依次交给模拟后端、反向代理处理，都不匹配时返回 404，因为它们是后端 API 调用
+        // 回放模式下优先使用录制的响应，录制模式下记录每次代理转发
+        if is_api_path(&path) {
+            if let Some(response) = mount.har.replay(request) {
+                return response;
+            }
+            if let Some(response) = mount.mock.as_ref().and_then(|mock| mock.respond(request, &path)) {
+                return response;
+            }
+            if let Some(proxy) = &mount.proxy {
+                let started = SystemTime::now();
+                let timer = Instant::now();
+                if let Some(result) = proxy.forward(request) {
+                    return match result {
+                        Ok(response) => {
+                            mount.har.record(request, &response, started, timer.elapsed());
+                            response
+                        }
+                        // 上游不可达的写请求进入离线队列，恢复连接后重试
+                        Err(ProxyError::Unreachable(e)) if queue::is_mutating(request.method()) => {
+                            self.enqueue_offline(project_id, request, e)
+                        }
+                        Err(e) => {
+                            println!("❌ {}", e);
+                            e.into_response()
+                        }
+                    };
+                }
+            }
+            println!("⚠️ API 请求，返回 404: {}", path);
+            return respond(
+                404,
+                Some("application/json"),
+                r#"{"error":"API endpoint not available in desktop app"}"#.as_bytes().to_vec(),
+            );
+        }
+
+        let path = with_default_document(path, &mount.entry);
+        println!("🎯 最终路径: {}", path);
+
+        // 查找文件；开启 SPA 回退时，找不到的前端路由（如 editor/42）返回入口文件
+        let located = match mount.source.metadata(&path) {
+            Err(AssetError::NotFound) if mount.spa_fallback && is_route_path(&path) => {
+                println!("🧭 SPA 回退到 {}: {}", mount.entry, path);
+                mount
+                    .source
+                    .metadata(&mount.entry)
+                    .map(|metadata| (mount.entry.clone(), metadata))
+            }
+            result => result.map(|metadata| (path.clone(), metadata)),
+        };
+
+        let served = located.and_then(|(served_path, metadata)| {
+            mount.serve(request, &self.asset_cache, project_id, &served_path, &metadata)
+        });
+        match served {
+            Ok(response) => response,
+            Err(e) => error_response(&path, e),
+        }
+    }
+}
+
+impl ProjectMount {
+    // 读取文件并构造响应，处理 HTML 注入、压缩、条件请求和 Range 请求
+    // 完整读取的内容连同 MIME、ETag 放入内存缓存，文件未变化时直接使用
+    fn serve(
+        &self,
+        request: &Request<Vec<u8>>,
+        asset_cache: &AssetCache,
+        project_id: &str,
+        path: &str,
+        metadata: &AssetMetadata,
+    ) -> Result<Response<Vec<u8>>, AssetError> {
+        let headers = request.headers();
+        let cached = asset_cache.get(project_id, path, metadata);
+        let mime_type = match &cached {
+            Some(asset) => asset.mime_type.clone(),
+            None => mime::mime_type(path, &self.mime_overrides),
+        };
+        let mime_type = mime_type.as_str();
+        println!("📝 Content-Type: {}", mime_type);
+
+        let cache_control = self.cache_policy.cache_control(path, mime_type);
+        // Range 只针对原始内容，分段请求不做压缩
+        let encodings = if headers.contains_key(header::RANGE) {
+            Vec::new()
+        } else {
+            compress::accepted_encodings(headers)
+        };
+
+        // 对所有 HTML 执行注入管线；注入后长度会变化，因此 HTML 不支持分段请求
+        // ETag 按注入后的内容计算，注入内容变化时也能让缓存失效
+        if mime::essence(mime_type) == "text/html" {
+            let asset = match cached {
+                Some(asset) => asset,
+                None => {
+                    let content = self.injectors.apply(self.source.read(path)?);
+                    let etag = cache::content_etag(&content);
+                    let asset = CachedAsset::new(content, mime_type.to_string(), Some(etag), None, metadata);
+                    asset_cache.insert(project_id, path, asset)
+                }
+            };
+            // 即时压缩时按将要返回的编码比较 ETag
+            let encoding = self.response_encoding(mime_type, asset.content.len() as u64, &encodings);
+            let response_etag = match encoding {
+                Some(encoding) => asset.etag.as_deref().map(|etag| compress::encoded_etag(etag, encoding)),
+                None => asset.etag.clone(),
+            };
+            if cache::is_not_modified(headers, response_etag.as_deref(), None) {
+                println!("♻️ 未修改，返回 304: {}", path);
+                return Ok(not_modified(cache_control, response_etag.as_deref(), None));
+            }
+            println!("✅ 文件读取成功，大小: {} bytes", asset.content.len());
+            let content = asset.content.clone();
+            return Ok(self.full_response(mime_type, cache_control, asset.etag.clone(), None, content, encoding));
+        }
+
+        // 优先使用预压缩文件，例如 foo.js.br、foo.js.gz
+        if self.compression.precompressed {
+            for encoding in &encodings {
+                let sibling = format!("{}.{}", path, encoding.extension());
+                let sibling_metadata = match self.source.metadata(&sibling) {
+                    Ok(sibling_metadata) => sibling_metadata,
+                    Err(_) => continue,
+                };
+                let etag = cache::metadata_etag(&sibling_metadata);
+                let last_modified = cache::last_modified(&sibling_metadata);
+                if cache::is_not_modified(headers, etag.as_deref(), sibling_metadata.modified) {
+                    println!("♻️ 未修改，返回 304: {}", sibling);
+                    return Ok(not_modified(cache_control, etag.as_deref(), last_modified.as_deref()));
+                }
+                let cached = asset_cache.get(project_id, &sibling, &sibling_metadata);
+                let sibling_mime = mime::mime_type(&sibling, &self.mime_overrides);
+                let asset = self.read_cached(asset_cache, project_id, &sibling, &sibling_metadata, &sibling_mime, cached)?;
+                println!("🗜️ 使用预压缩文件: {} ({} bytes)", sibling, asset.content.len());
+                return Ok(cached_builder(cache_control, etag.as_deref(), last_modified.as_deref())
+                    .status(200)
+                    .header(header::CONTENT_TYPE, mime_type)
+                    .header(header::CONTENT_ENCODING, encoding.name())
+                    .body(asset.content.clone())
+                    .unwrap());
+            }
+        }
+
+        let (etag, last_modified) = match &cached {
+            Some(asset) => (asset.etag.clone(), asset.last_modified.clone()),
+            None => (cache::metadata_etag(metadata), cache::last_modified(metadata)),
+        };
+
+        // 即时压缩：内容和压缩结果都是确定的，因此沿用文件的验证器，只区分 ETag
+        if let Some(encoding) = self.response_encoding(mime_type, metadata.len, &encodings) {
+            let response_etag = etag.as_deref().map(|etag| compress::encoded_etag(etag, encoding));
+            if cache::is_not_modified(headers, response_etag.as_deref(), metadata.modified) {
+                println!("♻️ 未修改，返回 304: {}", path);
+                return Ok(not_modified(cache_control, response_etag.as_deref(), last_modified.as_deref()));
+            }
+            let asset = self.read_cached(asset_cache, project_id, path, metadata, mime_type, cached)?;
+            let content = asset.content.clone();
+            return Ok(self.full_response(mime_type, cache_control, etag, last_modified, content, Some(encoding)));
+        }
+
+        if cache::is_not_modified(headers, etag.as_deref(), metadata.modified) {
+            println!("♻️ 未修改，返回 304: {}", path);
+            return Ok(not_modified(cache_control, etag.as_deref(), last_modified.as_deref()));
+        }
+
+        let builder = cached_builder(cache_control, etag.as_deref(), last_modified.as_deref())
+            .header(header::ACCEPT_RANGES, "bytes");
+
+        // If-Range 不匹配时忽略 Range，返回完整的新内容
+        let total = metadata.len;
+        let range = match headers.get(header::RANGE).and_then(|value| value.to_str().ok()) {
+            Some(value) if cache::if_range_matches(headers, etag.as_deref(), metadata.modified) => {
+                range::parse_range(value, total)
+            }
+            _ => RangeRequest::Full,
+        };
+
+        // 分段读取：已缓存时从缓存中截取，否则直接读取该段（不为分段请求缓存整个文件）
+        let read_range = |start: u64, len: u64| -> Result<Vec<u8>, AssetError> {
+            match &cached {
+                Some(asset) => {
+                    let start = (start as usize).min(asset.content.len());
+                    let end = start.saturating_add(len as usize).min(asset.content.len());
+                    Ok(asset.content[start..end].to_vec())
+                }
+                None => self.source.read_range(path, start, len),
+            }
+        };
+
+        let response = match range {
+            RangeRequest::Full => {
+                let asset = self.read_cached(asset_cache, project_id, path, metadata, mime_type, cached.clone())?;
+                println!("✅ 文件读取成功，大小: {} bytes", asset.content.len());
+                builder
+                    .status(200)
+                    .header(header::CONTENT_TYPE, mime_type)
+                    .body(asset.content.clone())
+            }
+            RangeRequest::Unsatisfiable => {
+                println!("⚠️ Range 超出文件长度 {}: {}", total, path);
+                builder
+                    .status(416)
+                    .header(header::CONTENT_RANGE, format!("bytes */{}", total))
+                    .body(Vec::new())
+            }
+            RangeRequest::Partial(ranges) if ranges.len() == 1 => {
+                let range = ranges[0];
+                let content = read_range(range.start, range.size())?;
+                println!("✂️ 分段响应: {}", range.content_range(total));
+                builder
+                    .status(206)
+                    .header(header::CONTENT_TYPE, mime_type)
+                    .header(header::CONTENT_RANGE, range.content_range(total))
+                    .body(content)
+            }
+            RangeRequest::Partial(ranges) => {
+                let mut parts = Vec::with_capacity(ranges.len());
+                for range in ranges {
+                    parts.push((range, read_range(range.start, range.size())?));
+                }
+                println!("✂️ 多段响应: {} 段", parts.len());
+                let boundary = range::multipart_boundary();
+                builder
+                    .status(206)
+                    .header(
+                        header::CONTENT_TYPE,
+                        format!("multipart/byteranges; boundary={}", boundary),
+                    )
+                    .body(range::multipart_body(&boundary, mime_type, total, &parts))
+            }
+        };
+        Ok(response.unwrap())
+    }
+
+    // 完整读取文件（非 HTML），未命中缓存时读取后放入缓存
+    fn read_cached(
+        &self,
+        asset_cache: &AssetCache,
+        project_id: &str,
+        path: &str,
+        metadata: &AssetMetadata,
+        mime_type: &str,
+        cached: Option<Arc<CachedAsset>>,
+    ) -> Result<Arc<CachedAsset>, AssetError> {
+        if let Some(asset) = cached {
+            return Ok(asset);
+        }
+        let asset = CachedAsset::new(
+            self.source.read(path)?,
+            mime_type.to_string(),
+            cache::metadata_etag(metadata),
+            cache::last_modified(metadata),
+            metadata,
+        );
+        Ok(asset_cache.insert(project_id, path, asset))
+    }
+
+    // 是否对该内容做即时压缩
+    fn should_compress(&self, mime_type: &str, len: u64) -> bool {
+        self.compression.on_the_fly && len >= self.compression.min_size && compress::is_compressible(mime_type)
+    }
+
+    // 满足即时压缩条件时返回要使用的编码（客户端最优先的一个）
+    fn response_encoding(&self, mime_type: &str, len: u64, encodings: &[Encoding]) -> Option<Encoding> {
+        encodings.first().copied().filter(|_| self.should_compress(mime_type, len))
+    }
+
+    // 完整内容的 200 响应，encoding 不为空时即时压缩
+    // etag 为未压缩内容的 ETag，压缩后改用对应编码的 ETag，避免不同编码共用同一个强 ETag
+    fn full_response(
+        &self,
+        mime_type: &str,
+        cache_control: &str,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        content: Vec<u8>,
+        encoding: Option<Encoding>,
+    ) -> Response<Vec<u8>> {
+        if let Some(encoding) = encoding {
+            match encoding.compress(&content) {
+                Ok(compressed) => {
+                    println!(
+                        "🗜️ 即时压缩 {}: {} -> {} bytes",
+                        encoding.name(),
+                        content.len(),
+                        compressed.len()
+                    );
+                    let etag = etag.as_deref().map(|etag| compress::encoded_etag(etag, encoding));
+                    return cached_builder(cache_control, etag.as_deref(), last_modified.as_deref())
+                        .status(200)
+                        .header(header::CONTENT_TYPE, mime_type)
+                        .header(header::CONTENT_ENCODING, encoding.name())
+                        .body(compressed)
+                        .unwrap();
+                }
+                Err(e) => println!("⚠️ 压缩失败，返回原始内容: {}", e),
+            }
+        }
+        cached_builder(cache_control, etag.as_deref(), last_modified.as_deref())
+            .status(200)
+            .header(header::CONTENT_TYPE, mime_type)
+            .body(content)
+            .unwrap()
+    }
+}
+
+// 200/206/304 响应共用的缓存相关头
+// 同一路径可能返回压缩或未压缩的表示，因此都带上 Vary: Accept-Encoding
+fn cached_builder(cache_control: &str, etag: Option<&str>, last_modified: Option<&str>) -> response::Builder {
+    let mut builder = Response::builder()
+        .header(header::CACHE_CONTROL, cache_control)
+        .header(header::VARY, "Accept-Encoding");
+    if let Some(etag) = etag {
+        builder = builder.header(header::ETAG, etag);
+    }
+    if let Some(last_modified) = last_modified {
+        builder = builder.header(header::LAST_MODIFIED, last_modified);
+    }
+    builder
+}
+
+// 304 Not Modified 响应
+fn not_modified(cache_control: &str, etag: Option<&str>, last_modified: Option<&str>) -> Response<Vec<u8>> {
+    cached_builder(cache_control, etag, last_modified)
+        .status(304)
+        .body(Vec::new())
+        .unwrap()
+}
+
+// 读取失败时的错误响应
+fn error_response(path: &str, error: AssetError) -> Response<Vec<u8>> {
+    match error {
+        AssetError::Forbidden => {
+            println!("⛔ 拒绝越界访问: {}", path);
+            respond(403, None, format!("禁止访问: {}", path).into_bytes())
+        }
+        AssetError::Tampered(reason) => {
+            println!("🛡️ 拒绝未通过校验的文件: {}", reason);
+            respond(403, Some("text/html; charset=utf-8"), tampered_page(path, &reason).into_bytes())
+        }
+        AssetError::NotFound => {
+            println!("❌ 文件不存在: {}", path);
+            respond(404, None, format!("文件不存在: {}", path).into_bytes())
+        }
+        AssetError::Io(e) => {
+            println!("❌ 文件读取失败: {}", e);
+            respond(500, None, format!("读取文件失败: {}", e).into_bytes())
+        }
+    }
+}
+
+// 完整性校验失败时的错误页面，不经过注入管线
+fn tampered_page(path: &str, reason: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head><meta charset=\"utf-8\"><title>文件校验失败</title></head>\n\
+         <body style=\"font-family: sans-serif; padding: 40px; color: #333;\">\n\
+         <h1>文件校验失败</h1>\n\
+         <p>项目文件 <code>{}</code> 未通过签名校验，可能已被篡改，已拒绝加载。</p>\n\
+         <p style=\"color: #888;\">{}</p>\n\
+         <p>请重新安装该项目。</p>\n</body>\n</html>\n",
+        inject::escape_attr(path),
+        inject::escape_attr(reason)
+    )
+}
+
+// 从 URL 中取出项目 ID（host）
+// Windows 上 WebView2 会把自定义协议改写为 http://myapp.{project_id}/，这里一并兼容
+pub fn project_id(uri: &Uri) -> Option<&str> {
+    let host = uri.host()?;
+    let prefix = format!("{}.", SCHEME);
+    let host = match uri.scheme_str() {
+        Some(scheme) if scheme != SCHEME => host.strip_prefix(prefix.as_str()).unwrap_or(host),
+        _ => host,
+    };
+    if host.is_empty() {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+// 从 URL 中取出项目内的相对路径：去掉开头的 "/"（查询参数和 hash 不属于 path），并做 URL 解码
+pub fn request_path(uri: &Uri) -> String {
+    let path = uri.path().trim_start_matches('/');
+
+    // URL 解码路径（处理 %20 等编码字符）
+    match urlencoding::decode(path) {
+        Ok(decoded) => decoded.to_string(),
+        Err(_) => path.to_string(),
+    }
+}
+
+// 是否为后端 API 路径（path 为项目内相对路径）
+pub fn is_api_path(path: &str) -> bool {
+    let path = format!("/{}", path);
+    path.contains("/session/") || path.contains("/ai/") || path.contains("/api/")
+}
+
+// 是否为前端路由路径：最后一段没有扩展名（或是 .html），而不是 js/css/图片等资源文件
+pub fn is_route_path(path: &str) -> bool {
+    let last_segment = path.rsplit('/').next().unwrap_or(path);
+    match std::path::Path::new(last_segment).extension().and_then(|s| s.to_str()) {
+        None => true,
+        Some(extension) => matches!(extension.to_ascii_lowercase().as_str(), "html" | "htm"),
+    }
+}
+
+// 如果路径为空，加载项目入口文件；以 "/" 结尾的子目录加载其中的 index.html
+pub fn with_default_document(path: String, entry: &str) -> String {
+    if path.is_empty() {
+        entry.to_string()
+    } else if path.ends_with('/') {
+        format!("{}{}", path, DEFAULT_DOCUMENT)
+    } else {
+        path
+    }
+}
+
+// 构造响应
+fn respond(status: u16, content_type: Option<&str>, body: Vec<u8>) -> Response<Vec<u8>> {
+    let mut builder = Response::builder().status(status);
+    if let Some(content_type) = content_type {
+        builder = builder.header(header::CONTENT_TYPE, content_type);
+    }
+    builder.body(body).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 内存中的资源来源；"locked/" 下的路径视为越界，"tampered/" 下的视为未通过校验
+    struct MemorySource(HashMap<&'static str, &'static str>);
+
+    impl AssetSource for MemorySource {
+        fn metadata(&self, path: &str) -> Result<AssetMetadata, AssetError> {
+            if path.starts_with("locked/") {
+                return Err(AssetError::Forbidden);
+            }
+            if path.starts_with("tampered/") {
+                return Err(AssetError::Tampered(format!("文件内容与签名清单不一致: {}", path)));
+            }
+            let content = self.0.get(path).ok_or(AssetError::NotFound)?;
+            Ok(AssetMetadata {
+                len: content.len() as u64,
+                modified: Some(SystemTime::UNIX_EPOCH),
+            })
+        }
+
+        fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+            self.metadata(path)?;
+            Ok(self.0[path].as_bytes().to_vec())
+        }
+    }
+
+    fn source(files: &[(&'static str, &'static str)]) -> MemorySource {
+        MemorySource(files.iter().copied().collect())
+    }
+
+    fn resolver() -> ProtocolResolver {
+        let mut resolver = ProtocolResolver::new()
+            .with_project(
+                "studio",
+                source(&[
+                    ("index.html", "<html><head></head><body>studio</body></html>"),
+                    ("app.js", "studio-js"),
+                    ("docs/index.html", "<html><body>docs</body></html>"),
+                ]),
+            )
+            .with_project("project2", source(&[("app.js", "project2-js")]));
+        resolver.mount(
+            "spa",
+            ProjectMount::new(source(&[("main.html", "<html><body>main</body></html>")]))
+                .entry("main.html")
+                .spa_fallback(true),
+        );
+        resolver
+    }
+
+    fn get(resolver: &ProtocolResolver, uri: &str) -> (u16, String) {
+        let request = Request::builder().uri(uri).body(Vec::new()).unwrap();
+        let response = resolver.resolve(&request);
+        (
+            response.status().as_u16(),
+            String::from_utf8_lossy(response.body()).into_owned(),
+        )
+    }
+
+    #[test]
+    fn host_selects_project() {
+        let resolver = resolver();
+        assert_eq!(get(&resolver, "myapp://studio/app.js"), (200, "studio-js".to_string()));
+        assert_eq!(get(&resolver, "myapp://project2/app.js"), (200, "project2-js".to_string()));
+        // Windows 上 WebView2 改写后的地址
+        assert_eq!(get(&resolver, "http://myapp.project2/app.js"), (200, "project2-js".to_string()));
+        // 同名文件不会串到其他项目
+        assert_eq!(get(&resolver, "myapp://project2/index.html").0, 404);
+    }
+
+    #[test]
+    fn unknown_or_missing_project_is_rejected() {
+        let resolver = resolver();
+        assert_eq!(get(&resolver, "myapp://unknown/app.js").0, 404);
+        assert_eq!(project_id(&"http://myapp.studio/a".parse().unwrap()), Some("studio"));
+        assert_eq!(project_id(&"myapp://studio/a".parse().unwrap()), Some("studio"));
+    }
+
+    #[test]
+    fn entry_and_default_document() {
+        let resolver = resolver();
+        let (status, body) = get(&resolver, "myapp://studio/");
+        assert_eq!(status, 200);
+        assert!(body.contains("studio"));
+        assert!(get(&resolver, "myapp://studio/docs/").1.contains("docs"));
+        // 自定义入口文件
+        let (status, body) = get(&resolver, "myapp://spa/");
+        assert_eq!(status, 200);
+        assert!(body.contains("main"));
+    }
+
+    #[test]
+    fn spa_fallback_only_for_routes() {
+        let resolver = resolver();
+        let (status, body) = get(&resolver, "myapp://spa/editor/42");
+        assert_eq!(status, 200);
+        assert!(body.contains("main"));
+        assert_eq!(get(&resolver, "myapp://spa/assets/missing.js").0, 404);
+        // 没有开启 SPA 回退的项目
+        assert_eq!(get(&resolver, "myapp://studio/editor/42").0, 404);
+    }
+
+    #[test]
+    fn asset_errors_map_to_status() {
+        let resolver = ProtocolResolver::new().with_project("p", source(&[("a.txt", "a")]));
+        assert_eq!(get(&resolver, "myapp://p/missing.txt").0, 404);
+        assert_eq!(get(&resolver, "myapp://p/locked/a.txt").0, 403);
+        let (status, body) = get(&resolver, "myapp://p/tampered/a.js");
+        assert_eq!(status, 403);
+        assert!(body.contains("文件校验失败"));
+        // API 路径不读取文件
+        assert_eq!(get(&resolver, "myapp://p/api/users").0, 404);
+    }
+
+    #[test]
+    fn compressed_html_has_its_own_etag() {
+        let mut resolver = ProtocolResolver::new();
+        resolver.mount(
+            "p",
+            ProjectMount::new(source(&[("index.html", "<html><body>compressed</body></html>")])).compression(CompressionConfig {
+                precompressed: false,
+                on_the_fly: true,
+                min_size: 0,
+            }),
+        );
+        let request = |encoding: Option<&str>, if_none_match: Option<&str>| {
+            let mut builder = Request::builder().uri("myapp://p/index.html");
+            if let Some(encoding) = encoding {
+                builder = builder.header(header::ACCEPT_ENCODING, encoding);
+            }
+            if let Some(etag) = if_none_match {
+                builder = builder.header(header::IF_NONE_MATCH, etag);
+            }
+            resolver.resolve(&builder.body(Vec::new()).unwrap())
+        };
+        let etag = |response: &Response<Vec<u8>>| response.headers()[header::ETAG].to_str().unwrap().to_string();
+
+        let identity = request(None, None);
+        assert!(identity.headers().get(header::CONTENT_ENCODING).is_none());
+        let brotli = request(Some("br"), None);
+        assert_eq!(brotli.headers()[header::CONTENT_ENCODING], "br");
+        assert_eq!(etag(&brotli), compress::encoded_etag(&etag(&identity), Encoding::Brotli));
+
+        // 未压缩表示的 ETag 不能让压缩请求返回 304，反之亦然
+        assert_eq!(request(Some("br"), Some(&etag(&identity))).status(), 200);
+        assert_eq!(request(None, Some(&etag(&brotli))).status(), 200);
+        assert_eq!(request(Some("br"), Some(&etag(&brotli))).status(), 304);
+        assert_eq!(request(None, Some(&etag(&identity))).status(), 304);
+    }
+
+    #[test]
+    fn only_full_reads_count_cache_misses() {
+        let resolver = resolver();
+        let range = Request::builder()
+            .uri("myapp://studio/app.js")
+            .header(header::RANGE, "bytes=0-3")
+            .body(Vec::new())
+            .unwrap();
+        assert_eq!(resolver.resolve(&range).status(), 206);
+        assert_eq!(resolver.asset_cache().stats().misses, 0);
+
+        assert_eq!(get(&resolver, "myapp://studio/app.js").0, 200);
+        assert_eq!(get(&resolver, "myapp://studio/app.js").0, 200);
+        let stats = resolver.asset_cache().stats();
+        assert_eq!((stats.misses, stats.hits, stats.entries), (1, 1, 1));
+    }
+}
@@ -0,0 +1,498 @@
+// 返回首页浮层：按项目配置文案、语言、位置、配色和快捷键，生成注入的样式和脚本
+use serde::{Deserialize, Serialize};
+
+use super::inject::Injector;
+
+// 浮层对应的注入器名称
+pub const BACK_HOME_STYLE_INJECTOR: &str = "back-home-style";
+pub const BACK_HOME_SCRIPT_INJECTOR: &str = "back-home-script";
+
+// 按钮所在的角落，键盘提示显示在同一侧的另一个角落
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayCorner {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl OverlayCorner {
+    fn edges(self) -> (&'static str, &'static str) {
+        match self {
+            OverlayCorner::TopLeft => ("top", "left"),
+            OverlayCorner::TopRight => ("top", "right"),
+            OverlayCorner::BottomLeft => ("bottom", "left"),
+            OverlayCorner::BottomRight => ("bottom", "right"),
+        }
+    }
+
+    // 按钮的定位
+    fn button_position(self) -> String {
+        let (vertical, horizontal) = self.edges();
+        format!("  {}: 16px !important;\n  {}: 16px !important;", vertical, horizontal)
+    }
+
+    // 键盘提示的定位（水平方向与按钮相反）
+    fn hint_position(self) -> String {
+        let (vertical, horizontal) = self.edges();
+        let horizontal = if horizontal == "left" { "right" } else { "left" };
+        format!("  {}: 16px !important;\n  {}: 16px !important;", vertical, horizontal)
+    }
+}
+
+// 按钮配色（CSS 值）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverlayTheme {
+    pub background: String,
+    pub color: String,
+    pub border: String,
+    pub shadow: String,
+    pub hover_shadow: String,
+}
+
+impl Default for OverlayTheme {
+    fn default() -> Self {
+        Self {
+            background: "linear-gradient(135deg, #667eea 0%, #764ba2 100%)".to_string(),
+            color: "#fff".to_string(),
+            border: "2px solid rgba(255,255,255,0.3)".to_string(),
+            shadow: "0 6px 20px rgba(102, 126, 234, 0.4)".to_string(),
+            hover_shadow: "0 8px 25px rgba(102, 126, 234, 0.6)".to_string(),
+        }
+    }
+}
+
+// 单个快捷键，例如 "Alt+H"、"Ctrl+Shift+K"、"Escape"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Shortcut {
+    // KeyboardEvent.key（小写）
+    pub key: String,
+    // KeyboardEvent.code，字母和数字键使用，避免 macOS 上 Alt 组合键改变 key
+    pub code: Option<String>,
+    pub alt: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+impl Shortcut {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut shortcut = Shortcut {
+            key: String::new(),
+            code: None,
+            alt: false,
+            ctrl: false,
+            shift: false,
+            meta: false,
+        };
+        let mut parts: Vec<&str> = value.split('+').map(str::trim).collect();
+        let key = parts.pop().filter(|key| !key.is_empty());
+        let key = key.ok_or_else(|| format!("快捷键缺少按键: {}", value))?;
+
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "alt" | "option" => shortcut.alt = true,
+                "ctrl" | "control" => shortcut.ctrl = true,
+                "shift" => shortcut.shift = true,
+                "meta" | "cmd" | "command" => shortcut.meta = true,
+                _ => return Err(format!("未知的修饰键 {}: {}", modifier, value)),
+            }
+        }
+
+        shortcut.key = key.to_lowercase();
+        let mut chars = key.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if c.is_ascii_alphabetic() {
+                shortcut.code = Some(format!("Key{}", c.to_ascii_uppercase()));
+            } else if c.is_ascii_digit() {
+                shortcut.code = Some(format!("Digit{}", c));
+            }
+        }
+        Ok(shortcut)
+    }
+}
+
+// 单个项目的返回首页浮层配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverlayConfig {
+    // 关闭后不注入按钮和快捷键
+    pub enabled: bool,
+    // 按钮文案，为空时使用 locale 对应的默认文案
+    pub label: Option<String>,
+    // 语言：zh-CN（默认）或 en
+    pub locale: String,
+    pub corner: OverlayCorner,
+    pub theme: OverlayTheme,
+    // 快捷键列表，为空时不注册快捷键、不显示提示
+    pub shortcuts: Vec<String>,
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            label: None,
+            locale: "zh-CN".to_string(),
+            corner: OverlayCorner::default(),
+            theme: OverlayTheme::default(),
+            shortcuts: vec!["Alt+H".to_string(), "Escape".to_string()],
+        }
+    }
+}
+
+impl OverlayConfig {
+    // 关闭浮层
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    fn is_english(&self) -> bool {
+        self.locale.to_ascii_lowercase().starts_with("en")
+    }
+
+    // 按钮文案
+    pub fn label(&self) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None if self.is_english() => "🏠 Home".to_string(),
+            None => "🏠 返回首页".to_string(),
+        }
+    }
+
+    // 键盘提示文案，只列出第一个快捷键
+    fn hint(&self) -> String {
+        match self.shortcuts.first() {
+            Some(shortcut) if self.is_english() => format!("{} to go home", shortcut),
+            Some(shortcut) => format!("{} 返回首页", shortcut),
+            None => String::new(),
+        }
+    }
+
+    // 解析快捷键，无效的快捷键忽略
+    fn parsed_shortcuts(&self) -> Vec<Shortcut> {
+        self.shortcuts
+            .iter()
+            .filter_map(|value| match Shortcut::parse(value) {
+                Ok(shortcut) => Some(shortcut),
+                Err(e) => {
+                    println!("⚠️ 忽略快捷键: {}", e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    // 生成浮层样式
+    pub fn style(&self) -> String {
+        let theme = &self.theme;
+        render_template(BACK_HOME_STYLE_TEMPLATE, |name| match name {
+            "button_position" => Some(self.corner.button_position()),
+            "hint_position" => Some(self.corner.hint_position()),
+            "background" => Some(css_value(&theme.background)),
+            "color" => Some(css_value(&theme.color)),
+            "border" => Some(css_value(&theme.border)),
+            "shadow" => Some(css_value(&theme.shadow)),
+            "hover_shadow" => Some(css_value(&theme.hover_shadow)),
+            _ => None,
+        })
+    }
+
+    // 生成浮层脚本
+    pub fn script(&self) -> String {
+        let shortcuts = serde_json::to_string(&self.parsed_shortcuts()).unwrap_or_else(|_| "[]".to_string());
+        render_template(BACK_HOME_SCRIPT_TEMPLATE, |name| match name {
+            "label" => Some(js_string(&self.label())),
+            "hint" => Some(js_string(&self.hint())),
+            "shortcuts" => Some(shortcuts.replace("</", "<\\/")),
+            _ => None,
+        })
+    }
+
+    // 浮层对应的注入器（样式在 </head> 前，脚本在 </body> 前）
+    pub fn injectors(&self) -> [Injector; 2] {
+        [
+            Injector::style(BACK_HOME_STYLE_INJECTOR, self.style()),
+            Injector::script(BACK_HOME_SCRIPT_INJECTOR, self.script()),
+        ]
+    }
+}
+
+// 一次替换模板中的所有 {{name}}，替换进去的值不会再被当作占位符；未知的占位符原样保留
+fn render_template(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}").and_then(|end| value(&after[..end]).map(|value| (end, value))) {
+            Some((end, value)) => {
+                output.push_str(&value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+// 去掉可能跳出当前 CSS 声明的字符
+fn css_value(value: &str) -> String {
+    value.chars().filter(|c| !matches!(c, ';' | '{' | '}' | '<' | '>')).collect()
+}
+
+// JS 字符串字面量，避免提前闭合 <script>
+fn js_string(value: &str) -> String {
+    serde_json::Value::from(value).to_string().replace("</", "<\\/")
+}
+
+// 浮层样式模板
+const BACK_HOME_STYLE_TEMPLATE: &str = r#"/* 返回首页按钮容器 */
+#tauri-back-home-container {
+  position: fixed !important;
+  top: 0 !important;
+  left: 0 !important;
+  width: 100vw !important;
+  height: 100vh !important;
+  z-index: 2147483647 !important;
+  pointer-events: none !important;
+  font-family: system-ui, -apple-system, sans-serif !important;
+}
+
+#tauri-back-home-btn {
+  position: absolute !important;
+{{button_position}}
+  z-index: 2147483647 !important;
+  padding: 12px 18px !important;
+  border-radius: 25px !important;
+  border: {{border}} !important;
+  background: {{background}} !important;
+  color: {{color}} !important;
+  font-weight: 700 !important;
+  box-shadow: {{shadow}} !important;
+  cursor: pointer !important;
+  font-size: 15px !important;
+  font-family: system-ui, -apple-system, sans-serif !important;
+  pointer-events: auto !important;
+  opacity: 0.95 !important;
+  transform: none !important;
+  transition: all 0.3s ease !important;
+  backdrop-filter: blur(10px) !important;
+  user-select: none !important;
+  display: flex !important;
+  align-items: center !important;
+  gap: 6px !important;
+}
+
+#tauri-back-home-btn:hover {
+  opacity: 1 !important;
+  transform: translateY(-2px) scale(1.05) !important;
+  box-shadow: {{hover_shadow}} !important;
+}
+
+#tauri-back-home-btn:active {
+  transform: translateY(0px) scale(0.98) !important;
+}
+
+/* 键盘提示 */
+#tauri-keyboard-hint {
+  position: absolute !important;
+{{hint_position}}
+  background: rgba(0,0,0,0.7) !important;
+  color: #fff !important;
+  padding: 8px 12px !important;
+  border-radius: 6px !important;
+  font-size: 12px !important;
+  pointer-events: none !important;
+  opacity: 0.8 !important;
+  backdrop-filter: blur(10px) !important;
+}
+"#;
+
+// 浮层脚本模板
+const BACK_HOME_SCRIPT_TEMPLATE: &str = r#"(function(){
+  const containerId = 'tauri-back-home-container';
+  const btnId = 'tauri-back-home-btn';
+  const hintId = 'tauri-keyboard-hint';
+
+  // 由 Rust 侧的 navigate_home 命令把主窗口导航回外壳前端（withGlobalTauri 提供 invoke）
+  function goHome() {
+    const invoke = window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke;
+    if (!invoke) {
+      console.warn('Tauri IPC 不可用，无法返回首页');
+      return;
+    }
+    invoke('navigate_home').catch((e) => console.error('❌ 返回首页失败:', e));
+  }
+
+  function createBackButton() {
+    // 清理旧容器
+    const old = document.getElementById(containerId);
+    if (old) old.remove();
+
+    const container = document.createElement('div');
+    container.id = containerId;
+
+    const btn = document.createElement('button');
+    btn.id = btnId;
+    btn.textContent = {{label}};
+
+    // 没有快捷键时不显示提示
+    const hintText = {{hint}};
+    const hint = document.createElement('div');
+    hint.id = hintId;
+    hint.textContent = hintText;
+
+    btn.addEventListener('click', (e) => {
+      e.preventDefault();
+      e.stopPropagation();
+      goHome();
+    }, true);
+
+    btn.addEventListener('mousedown', (e) => {
+      e.preventDefault();
+      e.stopPropagation();
+    }, true);
+
+    container.appendChild(btn);
+    if (hintText) container.appendChild(hint);
+    (document.body || document.documentElement).appendChild(container);
+    console.log('✅ 返回首页按钮已创建');
+  }
+
+  // 快捷键列表：{ key, code, alt, ctrl, shift, meta }
+  const shortcuts = {{shortcuts}};
+
+  function matchesShortcut(e, s) {
+    const key = (e.key || '').toLowerCase();
+    return (key === s.key || (s.code && e.code === s.code))
+      && e.altKey === s.alt && e.ctrlKey === s.ctrl
+      && e.shiftKey === s.shift && e.metaKey === s.meta;
+  }
+
+  function setupKeyboardShortcut() {
+    if (shortcuts.length === 0) return;
+    document.addEventListener('keydown', function(e) {
+      if (shortcuts.some((s) => matchesShortcut(e, s))) {
+        e.preventDefault();
+        e.stopPropagation();
+        goHome();
+      }
+    }, true);
+  }
+
+  function init() {
+    createBackButton();
+    setupKeyboardShortcut();
+  }
+
+  if (document.readyState === 'loading') {
+    document.addEventListener('DOMContentLoaded', init);
+  } else {
+    init();
+  }
+})();
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_substituted_once() {
+        let config = OverlayConfig {
+            label: Some("{{hint}} {{shortcuts}}".to_string()),
+            ..OverlayConfig::default()
+        };
+        let script = config.script();
+        // 文案中的占位符原样保留，不会被后面的替换再展开
+        assert!(script.contains(r#"btn.textContent = "{{hint}} {{shortcuts}}";"#), "{}", script);
+        assert!(script.contains(r#"const hintText = "Alt+H 返回首页";"#), "{}", script);
+        assert!(!script.contains("{{label}}"));
+
+        assert_eq!(render_template("a {{x}} {{unknown}} {{", |name| (name == "x").then(|| "{{x}}".to_string())), "a {{x}} {{unknown}} {{");
+    }
+
+    #[test]
+    fn corners_position_button_and_hint() {
+        for (corner, button, hint) in [
+            (OverlayCorner::TopLeft, ["top", "left"], ["top", "right"]),
+            (OverlayCorner::TopRight, ["top", "right"], ["top", "left"]),
+            (OverlayCorner::BottomLeft, ["bottom", "left"], ["bottom", "right"]),
+            (OverlayCorner::BottomRight, ["bottom", "right"], ["bottom", "left"]),
+        ] {
+            let style = OverlayConfig { corner, ..OverlayConfig::default() }.style();
+            let position = |[vertical, horizontal]: [&str; 2]| {
+                format!("  {}: 16px !important;\n  {}: 16px !important;", vertical, horizontal)
+            };
+            let button_rule = style.split("#tauri-back-home-btn {").nth(1).unwrap();
+            let hint_rule = style.split("#tauri-keyboard-hint {").nth(1).unwrap();
+            assert!(button_rule.starts_with(&format!("\n  position: absolute !important;\n{}", position(button))), "{:?}", corner);
+            assert!(hint_rule.starts_with(&format!("\n  position: absolute !important;\n{}", position(hint))), "{:?}", corner);
+            assert!(!style.contains("{{"), "{:?}", corner);
+        }
+    }
+
+    #[test]
+    fn theme_and_label_cannot_break_out() {
+        let config = OverlayConfig {
+            label: Some("</script><script>alert(1)</script>".to_string()),
+            theme: OverlayTheme {
+                background: "red; } </style><script>alert(1)</script>".to_string(),
+                ..OverlayTheme::default()
+            },
+            shortcuts: vec!["</script>".to_string()],
+            ..OverlayConfig::default()
+        };
+
+        let style = config.style();
+        assert!(style.contains("background: red  /stylescriptalert(1)/script !important;"), "{}", style);
+        assert!(!style.contains("</style>"));
+
+        let script = config.script();
+        assert!(!script.contains("</script>"), "{}", script);
+        assert!(script.contains(r#"btn.textContent = "<\/script><script>alert(1)<\/script>";"#), "{}", script);
+        assert!(script.contains(r#"const hintText = "<\/script> 返回首页";"#), "{}", script);
+    }
+
+    #[test]
+    fn shortcuts_are_rendered_as_json() {
+        let config = OverlayConfig {
+            locale: "en-US".to_string(),
+            shortcuts: vec!["Alt+H".to_string(), "Ctrl+Shift+1".to_string(), "Hyper+X".to_string(), "Escape".to_string()],
+            ..OverlayConfig::default()
+        };
+        let script = config.script();
+        let expected = serde_json::json!([
+            { "key": "h", "code": "KeyH", "alt": true, "ctrl": false, "shift": false, "meta": false },
+            { "key": "1", "code": "Digit1", "alt": false, "ctrl": true, "shift": true, "meta": false },
+            { "key": "escape", "code": null, "alt": false, "ctrl": false, "shift": false, "meta": false },
+        ]);
+        let rendered = script
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("const shortcuts = "))
+            .and_then(|json| json.strip_suffix(';'))
+            .unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(rendered).unwrap(), expected);
+        assert!(script.contains(r#"const hintText = "Alt+H to go home";"#), "{}", script);
+        assert!(script.contains(r#"btn.textContent = "🏠 Home";"#), "{}", script);
+
+        let none = OverlayConfig {
+            shortcuts: Vec::new(),
+            ..OverlayConfig::default()
+        };
+        let script = none.script();
+        assert!(script.contains("const shortcuts = [];"));
+        assert!(script.contains(r#"const hintText = "";"#));
+    }
+}
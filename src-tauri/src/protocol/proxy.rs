@@ -0,0 +1,310 @@
+// 反向代理：把项目发出的 API 请求按规则转发到上游服务器，并把上游响应原样返回
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tauri::http::{header, HeaderMap, HeaderName, HeaderValue, Request, Response};
+
+// 逐跳头，不能在代理两端之间转发
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+// 单条代理规则
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyRule {
+    // 匹配的路径前缀（项目内路径，以 "/" 开头），例如 "/api/"
+    pub prefix: String,
+    // 上游地址，例如 https://studio-server.jova.bio
+    pub upstream: String,
+    // 转发时把 prefix 替换为该值，例如 "/v2/"；为空时保留原路径
+    #[serde(default)]
+    pub rewrite: Option<String>,
+    // 注入的请求头，例如 Authorization
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    // 超时（毫秒）
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+impl ProxyRule {
+    // 转发目标 URL
+    pub fn target_url(&self, path: &str, query: Option<&str>) -> String {
+        let rest = &path[self.prefix.len()..];
+        let path = match &self.rewrite {
+            Some(rewrite) => format!("{}{}", rewrite, rest),
+            None => path.to_string(),
+        };
+        let mut url = format!("{}/{}", self.upstream.trim_end_matches('/'), path.trim_start_matches('/'));
+        if let Some(query) = query {
+            url.push('?');
+            url.push_str(query);
+        }
+        url
+    }
+}
+
+// 代理转发失败的原因
+#[derive(Debug)]
+pub enum ProxyError {
+    // 连接失败（上游不可达）
+    Unreachable(String),
+    // 超时
+    Timeout(String),
+    // 请求路径含有 "." 或 ".." 段，不转发
+    InvalidPath(String),
+    // 请求无法构造等其他错误
+    Other(String),
+}
+
+impl std::fmt::Display for ProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProxyError::Unreachable(e) => write!(f, "上游不可达: {}", e),
+            ProxyError::Timeout(e) => write!(f, "上游超时: {}", e),
+            ProxyError::InvalidPath(path) => write!(f, "非法的代理路径: {}", path),
+            ProxyError::Other(e) => write!(f, "代理失败: {}", e),
+        }
+    }
+}
+
+impl ProxyError {
+    // 网络错误（连接失败、超时）可以稍后重试，其他错误重试也不会成功
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ProxyError::Unreachable(_) | ProxyError::Timeout(_))
+    }
+
+    // 转换为返回给项目页面的错误响应
+    pub fn into_response(self) -> Response<Vec<u8>> {
+        let status = match self {
+            ProxyError::Timeout(_) => 504,
+            ProxyError::InvalidPath(_) => 400,
+            _ => 502,
+        };
+        Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(serde_json::json!({ "error": self.to_string() }).to_string().into_bytes())
+            .unwrap()
+    }
+}
+
+// 一个项目的反向代理
+pub struct ReverseProxy {
+    rules: Vec<ProxyRule>,
+    // 阻塞客户端内部会启动运行时，延迟到第一次转发时（在工作线程中）创建
+    client: OnceLock<reqwest::blocking::Client>,
+}
+
+impl ReverseProxy {
+    pub fn new(rules: Vec<ProxyRule>) -> Self {
+        Self {
+            rules,
+            client: OnceLock::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &[ProxyRule] {
+        &self.rules
+    }
+
+    // 查找匹配请求路径的第一条规则
+    pub fn find_rule(&self, path: &str) -> Option<&ProxyRule> {
+        self.rules.iter().find(|rule| path.starts_with(&rule.prefix))
+    }
+
+    // 转发请求；没有匹配的规则时返回 None
+    // 先检查路径再匹配规则，否则 /api/../admin 会匹配 /api/ 规则，上游解析后却落在规则之外
+    pub fn forward(&self, request: &Request<Vec<u8>>) -> Option<Result<Response<Vec<u8>>, ProxyError>> {
+        let path = request.uri().path();
+        if !is_normalized(path) {
+            return Some(Err(ProxyError::InvalidPath(path.to_string())));
+        }
+        let rule = self.find_rule(path)?;
+        Some(self.forward_with(rule, request))
+    }
+
+    fn forward_with(&self, rule: &ProxyRule, request: &Request<Vec<u8>>) -> Result<Response<Vec<u8>>, ProxyError> {
+        let url = rule.target_url(request.uri().path(), request.uri().query());
+        println!("🔀 代理转发: {} {} -> {}", request.method(), request.uri(), url);
+
+        let client = self.client.get_or_init(|| {
+            reqwest::blocking::Client::builder()
+                // 重定向交给项目页面自己处理
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .unwrap_or_else(|_| reqwest::blocking::Client::new())
+        });
+
+        let method = reqwest::Method::from_bytes(request.method().as_str().as_bytes())
+            .map_err(|e| ProxyError::Other(e.to_string()))?;
+        let mut builder = client
+            .request(method, &url)
+            .timeout(Duration::from_millis(rule.timeout_ms))
+            .body(request.body().clone());
+
+        for (name, value) in &upstream_headers(rule, request.headers()) {
+            builder = builder.header(name.as_str(), value.as_bytes());
+        }
+
+        let upstream = builder.send().map_err(|e| {
+            if e.is_timeout() {
+                ProxyError::Timeout(e.to_string())
+            } else if e.is_connect() {
+                ProxyError::Unreachable(e.to_string())
+            } else {
+                ProxyError::Other(e.to_string())
+            }
+        })?;
+
+        let status = upstream.status().as_u16();
+        let mut response = Response::builder().status(status);
+        for (name, value) in upstream.headers() {
+            if is_forwardable(name) && name != header::CONTENT_LENGTH {
+                response = response.header(name.as_str(), value.as_bytes());
+            }
+        }
+        let body = upstream.bytes().map_err(|e| {
+            if e.is_timeout() {
+                ProxyError::Timeout(e.to_string())
+            } else {
+                ProxyError::Other(e.to_string())
+            }
+        })?;
+        println!("✅ 上游响应: {} ({} bytes)", status, body.len());
+
+        response
+            .body(body.to_vec())
+            .map_err(|e| ProxyError::Other(e.to_string()))
+    }
+}
+
+// 路径（解码后，包括 %2e%2e、%2f 等编码形式）中没有 "." 或 ".." 段
+fn is_normalized(path: &str) -> bool {
+    let Ok(decoded) = urlencoding::decode(path) else {
+        return false;
+    };
+    !decoded.split(['/', '\\']).any(|segment| segment == "." || segment == "..")
+}
+
+fn is_forwardable(name: &HeaderName) -> bool {
+    !HOP_BY_HOP_HEADERS.contains(&name.as_str())
+}
+
+// 发往上游的请求头：去掉逐跳头、Host 和 Content-Length；规则中的请求头覆盖客户端发来的同名请求头
+fn upstream_headers(rule: &ProxyRule, headers: &HeaderMap) -> HeaderMap {
+    let mut result = HeaderMap::new();
+    for (name, value) in headers {
+        if is_forwardable(name) && name != header::HOST && name != header::CONTENT_LENGTH {
+            result.append(name.clone(), value.clone());
+        }
+    }
+    for (name, value) in &rule.headers {
+        match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+            (Ok(name), Ok(value)) => {
+                result.insert(name, value);
+            }
+            _ => println!("⚠️ 代理规则中的请求头无效: {}", name),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(prefix: &str, upstream: &str, rewrite: Option<&str>) -> ProxyRule {
+        ProxyRule {
+            prefix: prefix.to_string(),
+            upstream: upstream.to_string(),
+            rewrite: rewrite.map(str::to_string),
+            headers: HashMap::new(),
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+
+    #[test]
+    fn target_url_keeps_or_rewrites_prefix() {
+        let keep = rule("/api/", "https://example.com/", None);
+        assert_eq!(keep.target_url("/api/users/1", None), "https://example.com/api/users/1");
+        assert_eq!(keep.target_url("/api/users", Some("page=2&q=a%20b")), "https://example.com/api/users?page=2&q=a%20b");
+
+        let rewrite = rule("/api/", "https://example.com", Some("/v2/"));
+        assert_eq!(rewrite.target_url("/api/users/1", None), "https://example.com/v2/users/1");
+        assert_eq!(rewrite.target_url("/api/", None), "https://example.com/v2/");
+
+        // 重写为空时去掉前缀
+        let strip = rule("/ai/", "https://example.com/base", Some(""));
+        assert_eq!(strip.target_url("/ai/chat", Some("stream=1")), "https://example.com/base/chat?stream=1");
+    }
+
+    #[test]
+    fn dot_segments_are_rejected_before_matching() {
+        for path in ["/api/users", "/api/a.b/c", "/api/..well-known"] {
+            assert!(is_normalized(path), "{}", path);
+        }
+        for path in ["/api/../admin", "/api/./users", "/api/%2e%2e/admin", "/api/%2E%2E%2Fadmin", "/api/..\\admin"] {
+            assert!(!is_normalized(path), "{}", path);
+        }
+
+        // 路径不合法时不会匹配规则，也不会发出请求
+        let proxy = ReverseProxy::new(vec![rule("/api/", "http://127.0.0.1:9", None)]);
+        let request = Request::builder().uri("myapp://studio/api/%2e%2e/admin").body(Vec::new()).unwrap();
+        match proxy.forward(&request) {
+            Some(Err(ProxyError::InvalidPath(path))) => assert_eq!(path, "/api/%2e%2e/admin"),
+            other => panic!("{:?}", other.map(|result| result.map(|response| response.status()))),
+        }
+        let request = Request::builder().uri("myapp://studio/other").body(Vec::new()).unwrap();
+        assert!(proxy.forward(&request).is_none());
+    }
+
+    #[test]
+    fn rule_headers_override_client_headers() {
+        let mut rule = rule("/api/", "https://example.com", None);
+        rule.headers.insert("Authorization".to_string(), "Bearer server".to_string());
+        rule.headers.insert("X-Client".to_string(), "shell".to_string());
+
+        let request = Request::builder()
+            .uri("myapp://studio/api/users")
+            .header("authorization", "Bearer client")
+            .header("x-client", "page")
+            .header("accept", "application/json")
+            .header("host", "studio")
+            .header("content-length", "0")
+            .header("connection", "keep-alive")
+            .body(Vec::<u8>::new())
+            .unwrap();
+        let headers = upstream_headers(&rule, request.headers());
+
+        let values = |name: &str| headers.get_all(name).iter().map(|value| value.to_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(values("authorization"), ["Bearer server"]);
+        assert_eq!(values("x-client"), ["shell"]);
+        assert_eq!(values("accept"), ["application/json"]);
+        for name in ["host", "content-length", "connection"] {
+            assert!(headers.get(name).is_none(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn only_network_errors_are_retryable() {
+        assert!(ProxyError::Unreachable(String::new()).is_retryable());
+        assert!(ProxyError::Timeout(String::new()).is_retryable());
+        assert!(!ProxyError::InvalidPath(String::new()).is_retryable());
+        assert!(!ProxyError::Other(String::new()).is_retryable());
+    }
+}
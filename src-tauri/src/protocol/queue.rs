@@ -0,0 +1,311 @@
+// 离线写队列：上游不可达时，把 POST/PUT/PATCH/DELETE 请求持久化到磁盘，恢复连接后重试
+use std::path::PathBuf;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::http::{Method, Request};
+
+// 重试间隔：2s、4s、8s……最长 5 分钟
+const BASE_BACKOFF_MS: u64 = 2_000;
+const MAX_BACKOFF_MS: u64 = 5 * 60 * 1000;
+
+// 队列中的一个请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedRequest {
+    pub id: String,
+    pub project_id: String,
+    pub method: String,
+    // 原始的 myapp:// URL
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    // 请求体（base64）
+    pub body: String,
+    // 入队时间（毫秒时间戳）
+    pub queued_at: u64,
+    // 已重试次数
+    pub attempts: u32,
+    // 下次重试时间（毫秒时间戳）
+    pub next_attempt_at: u64,
+    pub last_error: Option<String>,
+    // 永久失败（项目已卸载、没有代理规则、请求无法还原等），不再自动重试，可以手动重试或丢弃
+    #[serde(default)]
+    pub failed: bool,
+}
+
+impl QueuedRequest {
+    // 还原为可以再次转发的请求
+    pub fn to_request(&self) -> Result<Request<Vec<u8>>, String> {
+        let body = base64::engine::general_purpose::STANDARD
+            .decode(&self.body)
+            .map_err(|e| format!("请求体解码失败: {}", e))?;
+        let mut builder = Request::builder().method(self.method.as_str()).uri(self.url.as_str());
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        builder.body(body).map_err(|e| format!("请求还原失败: {}", e))
+    }
+}
+
+// 是否为需要进入离线队列的写请求
+pub fn is_mutating(method: &Method) -> bool {
+    matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE)
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// 持久化的离线队列，所有修改都会立即写回文件
+pub struct OfflineQueue {
+    path: PathBuf,
+    items: Mutex<Vec<QueuedRequest>>,
+    // 唤醒重试线程（新请求入队或手动重试时）
+    wakeup: Condvar,
+}
+
+impl OfflineQueue {
+    // 从文件加载队列，文件不存在时为空队列
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let items = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("解析离线队列失败 {:?}: {}", path, e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(format!("读取离线队列失败 {:?}: {}", path, e)),
+        };
+        Ok(Self {
+            path,
+            items: Mutex::new(items),
+            wakeup: Condvar::new(),
+        })
+    }
+
+    // 先写临时文件再重命名，避免写到一半时崩溃导致队列文件损坏
+    fn persist(&self, items: &[QueuedRequest]) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("创建离线队列目录失败 {:?}: {}", parent, e))?;
+        }
+        let content = serde_json::to_vec_pretty(items).map_err(|e| format!("序列化离线队列失败: {}", e))?;
+        let temp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content)
+            .and_then(|_| std::fs::rename(&temp_path, &self.path))
+            .map_err(|e| format!("写入离线队列失败 {:?}: {}", self.path, e))
+    }
+
+    // 请求入队，返回队列中的记录
+    pub fn enqueue(&self, project_id: &str, request: &Request<Vec<u8>>) -> Result<QueuedRequest, String> {
+        let now = now_millis();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let queued = QueuedRequest {
+            id: format!("{:x}", nanos),
+            project_id: project_id.to_string(),
+            method: request.method().to_string(),
+            url: request.uri().to_string(),
+            headers: request
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    value.to_str().ok().map(|value| (name.to_string(), value.to_string()))
+                })
+                .collect(),
+            body: base64::engine::general_purpose::STANDARD.encode(request.body()),
+            queued_at: now,
+            attempts: 0,
+            next_attempt_at: now + BASE_BACKOFF_MS,
+            last_error: None,
+            failed: false,
+        };
+
+        let mut items = self.items.lock().unwrap();
+        items.push(queued.clone());
+        self.persist(&items)?;
+        self.wakeup.notify_all();
+        Ok(queued)
+    }
+
+    pub fn list(&self) -> Vec<QueuedRequest> {
+        self.items.lock().unwrap().clone()
+    }
+
+    // 到期需要重试的请求，不包括永久失败的请求
+    pub fn due(&self) -> Vec<QueuedRequest> {
+        let now = now_millis();
+        self.items
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|item| !item.failed && item.next_attempt_at <= now)
+            .cloned()
+            .collect()
+    }
+
+    // 移除请求（完成或手动丢弃），返回被移除的记录
+    pub fn remove(&self, id: &str) -> Result<Option<QueuedRequest>, String> {
+        let mut items = self.items.lock().unwrap();
+        let index = match items.iter().position(|item| item.id == id) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let removed = items.remove(index);
+        self.persist(&items)?;
+        Ok(Some(removed))
+    }
+
+    // 记录一次失败的重试，按指数退避安排下一次
+    pub fn mark_failed(&self, id: &str, error: &str) -> Result<(), String> {
+        let mut items = self.items.lock().unwrap();
+        if let Some(item) = items.iter_mut().find(|item| item.id == id) {
+            item.attempts += 1;
+            let backoff = BASE_BACKOFF_MS
+                .saturating_mul(1u64 << item.attempts.min(16))
+                .min(MAX_BACKOFF_MS);
+            item.next_attempt_at = now_millis() + backoff;
+            item.last_error = Some(error.to_string());
+        }
+        self.persist(&items)
+    }
+
+    // 记录永久失败：不再自动重试，保留在队列中等待手动重试或丢弃
+    pub fn mark_permanent_failure(&self, id: &str, error: &str) -> Result<(), String> {
+        let mut items = self.items.lock().unwrap();
+        if let Some(item) = items.iter_mut().find(|item| item.id == id) {
+            item.attempts += 1;
+            item.failed = true;
+            item.last_error = Some(error.to_string());
+        }
+        self.persist(&items)
+    }
+
+    // 立即重试：id 为 None 时重试全部（包括永久失败的请求），返回受影响的数量
+    pub fn retry_now(&self, id: Option<&str>) -> Result<usize, String> {
+        let mut items = self.items.lock().unwrap();
+        let mut count = 0;
+        for item in items.iter_mut() {
+            if id.is_none_or(|id| item.id == id) {
+                item.next_attempt_at = 0;
+                item.failed = false;
+                count += 1;
+            }
+        }
+        self.persist(&items)?;
+        self.wakeup.notify_all();
+        Ok(count)
+    }
+
+    // 重试线程等待：直到被唤醒或超时
+    pub fn wait(&self, timeout: Duration) {
+        let items = self.items.lock().unwrap();
+        let _ = self.wakeup.wait_timeout(items, timeout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(body: &str) -> Request<Vec<u8>> {
+        Request::builder()
+            .method("POST")
+            .uri("myapp://studio/api/notes?draft=1")
+            .header("content-type", "application/json")
+            .body(body.as_bytes().to_vec())
+            .unwrap()
+    }
+
+    #[test]
+    fn enqueued_requests_survive_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queue/offline.json");
+        let queue = OfflineQueue::open(path.clone()).unwrap();
+        assert!(queue.list().is_empty());
+
+        let queued = queue.enqueue("studio", &request(r#"{"title":"a"}"#)).unwrap();
+        assert_eq!(queued.attempts, 0);
+        assert!(queued.next_attempt_at > queued.queued_at);
+        // 刚入队的请求要等第一次退避之后才到期
+        assert!(queue.due().is_empty());
+
+        let reloaded = OfflineQueue::open(path).unwrap();
+        let items = reloaded.list();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, queued.id);
+        assert_eq!(items[0].project_id, "studio");
+
+        let restored = items[0].to_request().unwrap();
+        assert_eq!(restored.method(), Method::POST);
+        assert_eq!(restored.uri(), "myapp://studio/api/notes?draft=1");
+        assert_eq!(restored.headers()["content-type"], "application/json");
+        assert_eq!(restored.body(), br#"{"title":"a"}"#);
+    }
+
+    #[test]
+    fn backoff_grows_with_each_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = OfflineQueue::open(dir.path().join("offline.json")).unwrap();
+        let queued = queue.enqueue("studio", &request("")).unwrap();
+
+        let mut delays = Vec::new();
+        for _ in 0..3 {
+            let before = now_millis();
+            queue.mark_failed(&queued.id, "上游不可达").unwrap();
+            let after = now_millis();
+            let item = queue.list().remove(0);
+            assert!(item.next_attempt_at >= before);
+            delays.push((item.next_attempt_at - before, item.next_attempt_at - after));
+        }
+        // 2s * 2^attempts：4s、8s、16s
+        for ((upper, lower), expected) in delays.into_iter().zip([4_000, 8_000, 16_000]) {
+            assert!(lower <= expected && expected <= upper, "{} {} {}", lower, expected, upper);
+        }
+
+        let item = queue.list().remove(0);
+        assert_eq!(item.attempts, 3);
+        assert_eq!(item.last_error.as_deref(), Some("上游不可达"));
+
+        for _ in 0..20 {
+            queue.mark_failed(&queued.id, "上游不可达").unwrap();
+        }
+        let item = queue.list().remove(0);
+        assert!(item.next_attempt_at <= now_millis() + MAX_BACKOFF_MS);
+    }
+
+    #[test]
+    fn permanent_failures_are_not_retried_until_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = OfflineQueue::open(dir.path().join("offline.json")).unwrap();
+        let queued = queue.enqueue("studio", &request("")).unwrap();
+        queue.retry_now(None).unwrap();
+        assert_eq!(queue.due().len(), 1);
+
+        queue.mark_permanent_failure(&queued.id, "项目不存在").unwrap();
+        assert!(queue.due().is_empty());
+        assert!(queue.list()[0].failed);
+
+        assert_eq!(queue.retry_now(Some(&queued.id)).unwrap(), 1);
+        assert_eq!(queue.due().len(), 1);
+    }
+
+    #[test]
+    fn remove_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("offline.json");
+        let queue = OfflineQueue::open(path.clone()).unwrap();
+        let first = queue.enqueue("studio", &request("1")).unwrap();
+        let second = queue.enqueue("studio", &request("2")).unwrap();
+
+        assert_eq!(queue.remove(&first.id).unwrap().map(|item| item.id), Some(first.id.clone()));
+        assert!(queue.remove(&first.id).unwrap().is_none());
+
+        let ids: Vec<String> = OfflineQueue::open(path).unwrap().list().into_iter().map(|item| item.id).collect();
+        assert_eq!(ids, [second.id]);
+    }
+}
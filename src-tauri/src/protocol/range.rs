@@ -0,0 +1,281 @@
+// HTTP Range 请求解析（RFC 9110 §14），用于媒体拖动进度条和大文件分段读取
+
+// 一次请求最多接受的区间数，超过时忽略 Range 头返回完整内容，避免被大量小区间拖慢
+const MAX_RANGES: usize = 32;
+
+// 闭区间 [start, end]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    // 区间长度（字节）
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    // Content-Range 头的值，例如 "bytes 0-499/1234"
+    pub fn content_range(&self, total: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, total)
+    }
+}
+
+// Range 头的解析结果
+#[derive(Debug, PartialEq, Eq)]
+pub enum RangeRequest {
+    // 没有 Range 头，或格式无法识别：返回完整内容
+    Full,
+    // 可满足的区间（已排序并合并重叠部分）
+    Partial(Vec<ByteRange>),
+    // 所有区间都超出文件长度：返回 416
+    Unsatisfiable,
+}
+
+// 解析 Range 头，total 为文件总长度
+pub fn parse_range(header: &str, total: u64) -> RangeRequest {
+    let header = header.trim();
+    let specs = match header.split_once('=') {
+        Some((unit, specs)) if unit.trim().eq_ignore_ascii_case("bytes") => specs,
+        _ => return RangeRequest::Full,
+    };
+
+    let specs: Vec<&str> = specs.split(',').map(str::trim).collect();
+    if specs.len() > MAX_RANGES {
+        return RangeRequest::Full;
+    }
+
+    let mut ranges = Vec::new();
+    for spec in specs {
+        let (first, last) = match spec.split_once('-') {
+            Some(parts) => parts,
+            None => return RangeRequest::Full,
+        };
+        let (first, last) = (first.trim(), last.trim());
+
+        if first.is_empty() {
+            // 后缀区间 "-500"：最后 500 字节
+            let suffix: u64 = match last.parse() {
+                Ok(suffix) => suffix,
+                Err(_) => return RangeRequest::Full,
+            };
+            if suffix == 0 || total == 0 {
+                continue;
+            }
+            ranges.push(ByteRange {
+                start: total.saturating_sub(suffix),
+                end: total - 1,
+            });
+        } else {
+            let start: u64 = match first.parse() {
+                Ok(start) => start,
+                Err(_) => return RangeRequest::Full,
+            };
+            let end: Option<u64> = if last.is_empty() {
+                None
+            } else {
+                match last.parse() {
+                    Ok(end) => Some(end),
+                    Err(_) => return RangeRequest::Full,
+                }
+            };
+            // 结束位置小于起始位置属于语法错误，整个 Range 头无效
+            if matches!(end, Some(end) if end < start) {
+                return RangeRequest::Full;
+            }
+            // 起始位置超出文件长度的区间不可满足
+            if start >= total {
+                continue;
+            }
+            ranges.push(ByteRange {
+                start,
+                end: end.map_or(total - 1, |end| end.min(total - 1)),
+            });
+        }
+    }
+
+    if ranges.is_empty() {
+        return RangeRequest::Unsatisfiable;
+    }
+    RangeRequest::Partial(coalesce(ranges))
+}
+
+// 排序并合并重叠或相邻的区间
+fn coalesce(mut ranges: Vec<ByteRange>) -> Vec<ByteRange> {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<ByteRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end.saturating_add(1) => {
+                last.end = last.end.max(range.end);
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+// 多区间响应的 multipart/byteranges 分隔符
+pub fn multipart_boundary() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!("myapp-byteranges-{:x}", nanos)
+}
+
+// 组装 multipart/byteranges 响应体，parts 为每个区间及其内容
+pub fn multipart_body(
+    boundary: &str,
+    content_type: &str,
+    total: u64,
+    parts: &[(ByteRange, Vec<u8>)],
+) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (range, content) in parts {
+        body.extend_from_slice(
+            format!(
+                "\r\n--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n",
+                boundary,
+                content_type,
+                range.content_range(total)
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(content);
+    }
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{FsAssetSource, ProtocolResolver};
+    use tauri::http::{header, Request, Response};
+
+    fn range(start: u64, end: u64) -> ByteRange {
+        ByteRange { start, end }
+    }
+
+    #[test]
+    fn single_ranges() {
+        assert_eq!(parse_range("bytes=0-4", 10), RangeRequest::Partial(vec![range(0, 4)]));
+        // 结束位置超出文件长度时截断
+        assert_eq!(parse_range("bytes=5-100", 10), RangeRequest::Partial(vec![range(5, 9)]));
+        // 开放区间 "N-"
+        assert_eq!(parse_range("bytes=7-", 10), RangeRequest::Partial(vec![range(7, 9)]));
+        // 后缀区间 "-N"，超过文件长度时取整个文件
+        assert_eq!(parse_range("bytes=-3", 10), RangeRequest::Partial(vec![range(7, 9)]));
+        assert_eq!(parse_range("bytes=-30", 10), RangeRequest::Partial(vec![range(0, 9)]));
+        assert_eq!(parse_range(" Bytes = 1-1 ", 10), RangeRequest::Partial(vec![range(1, 1)]));
+    }
+
+    #[test]
+    fn multiple_ranges_are_sorted_and_merged() {
+        assert_eq!(
+            parse_range("bytes=6-7, 0-1, 1-3", 10),
+            RangeRequest::Partial(vec![range(0, 3), range(6, 7)])
+        );
+        // 相邻区间合并
+        assert_eq!(parse_range("bytes=0-1,2-3", 10), RangeRequest::Partial(vec![range(0, 3)]));
+        // 不可满足的区间被忽略
+        assert_eq!(parse_range("bytes=0-1,50-60", 10), RangeRequest::Partial(vec![range(0, 1)]));
+    }
+
+    #[test]
+    fn too_many_ranges_fall_back_to_full() {
+        let specs: Vec<String> = (0..MAX_RANGES as u64).map(|i| format!("{}-{}", i * 2, i * 2)).collect();
+        let header = format!("bytes={}", specs.join(","));
+        assert!(matches!(parse_range(&header, 1000), RangeRequest::Partial(ranges) if ranges.len() == MAX_RANGES));
+        let header = format!("{},{}-{}", header, 900, 901);
+        assert_eq!(parse_range(&header, 1000), RangeRequest::Full);
+    }
+
+    #[test]
+    fn invalid_headers_fall_back_to_full() {
+        for header in ["items=0-1", "bytes=abc", "bytes=5-2", "bytes=1", "bytes=-x", "bytes=0-1,x-"] {
+            assert_eq!(parse_range(header, 10), RangeRequest::Full, "{}", header);
+        }
+    }
+
+    #[test]
+    fn unsatisfiable_ranges() {
+        assert_eq!(parse_range("bytes=10-", 10), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range("bytes=20-30,40-", 10), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 10), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-", 0), RangeRequest::Unsatisfiable);
+    }
+
+    #[test]
+    fn multipart_body_lists_every_part() {
+        let body = multipart_body("b", "text/plain", 10, &[(range(0, 1), b"ab".to_vec()), (range(5, 6), b"fg".to_vec())]);
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.contains("--b\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-1/10\r\n\r\nab"));
+        assert!(body.contains("Content-Range: bytes 5-6/10\r\n\r\nfg"));
+        assert!(body.ends_with("\r\n--b--\r\n"));
+    }
+
+    fn fixture() -> (tempfile::TempDir, ProtocolResolver) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("data.bin"), b"0123456789").unwrap();
+        let resolver = ProtocolResolver::new().with_project("p", FsAssetSource::new(dir.path()));
+        (dir, resolver)
+    }
+
+    fn get(resolver: &ProtocolResolver, headers: &[(header::HeaderName, &str)]) -> Response<Vec<u8>> {
+        let mut request = Request::builder().uri("myapp://p/data.bin");
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+        resolver.resolve(&request.body(Vec::new()).unwrap())
+    }
+
+    #[test]
+    fn unsatisfiable_range_returns_416() {
+        let (_dir, resolver) = fixture();
+        let response = get(&resolver, &[(header::RANGE, "bytes=50-")]);
+        assert_eq!(response.status(), 416);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes */10");
+        assert!(response.body().is_empty());
+    }
+
+    #[test]
+    fn partial_and_multipart_responses() {
+        let (_dir, resolver) = fixture();
+        let response = get(&resolver, &[(header::RANGE, "bytes=-3")]);
+        assert_eq!(response.status(), 206);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 7-9/10");
+        assert_eq!(response.body(), b"789");
+
+        let response = get(&resolver, &[(header::RANGE, "bytes=0-1,8-")]);
+        assert_eq!(response.status(), 206);
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap();
+        assert!(content_type.starts_with("multipart/byteranges; boundary="));
+        let body = String::from_utf8_lossy(response.body());
+        assert!(body.contains("bytes 0-1/10\r\n\r\n01") && body.contains("bytes 8-9/10\r\n\r\n89"));
+    }
+
+    #[test]
+    fn if_range_with_etag_and_date() {
+        let (_dir, resolver) = fixture();
+        let full = get(&resolver, &[]);
+        let etag = full.headers()[header::ETAG].to_str().unwrap().to_string();
+        let last_modified = full.headers()[header::LAST_MODIFIED].to_str().unwrap().to_string();
+
+        // 验证器匹配：返回分段
+        for validator in [etag.as_str(), last_modified.as_str()] {
+            let response = get(&resolver, &[(header::RANGE, "bytes=0-1"), (header::IF_RANGE, validator)]);
+            assert_eq!(response.status(), 206, "{}", validator);
+        }
+        // 验证器不匹配（或弱 ETag）：忽略 Range，返回完整内容
+        let stale_date = httpdate::fmt_http_date(std::time::UNIX_EPOCH);
+        let weak = format!("W/{}", etag);
+        for validator in ["\"other\"", weak.as_str(), stale_date.as_str(), "garbage"] {
+            let response = get(&resolver, &[(header::RANGE, "bytes=0-1"), (header::IF_RANGE, validator)]);
+            assert_eq!(response.status(), 200, "{}", validator);
+            assert_eq!(response.body(), b"0123456789");
+        }
+    }
+}
@@ -0,0 +1,177 @@
+// 资源来源：协议解析器通过它按相对路径读取文件内容
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+
+// 读取资源时可能出现的错误
+#[derive(Debug)]
+pub enum AssetError {
+    // 文件不存在
+    NotFound,
+    // 路径越出项目根目录（路径穿越、符号链接逃逸等）
+    Forbidden,
+    // 文件未通过完整性校验（被篡改或不在签名清单中）
+    Tampered(String),
+    // 其他 IO 错误（权限、读取失败等）
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for AssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetError::NotFound => write!(f, "文件不存在"),
+            AssetError::Forbidden => write!(f, "禁止访问"),
+            AssetError::Tampered(reason) => write!(f, "{}", reason),
+            AssetError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for AssetError {
+    fn from(e: std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            AssetError::NotFound
+        } else {
+            AssetError::Io(e)
+        }
+    }
+}
+
+// 资源元数据
+#[derive(Debug, Clone)]
+pub struct AssetMetadata {
+    // 文件大小（字节）
+    pub len: u64,
+    // 最后修改时间，来源不支持时为 None
+    pub modified: Option<std::time::SystemTime>,
+}
+
+// 可插拔的资源来源，path 为已解码、不以 "/" 开头的相对路径
+pub trait AssetSource: Send + Sync {
+    // 读取元数据，不读取内容；文件不存在时返回 NotFound
+    fn metadata(&self, path: &str) -> Result<AssetMetadata, AssetError>;
+
+    // 读取完整内容
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError>;
+
+    // 读取从 start 开始的 len 个字节，默认实现读取完整内容后截取
+    fn read_range(&self, path: &str, start: u64, len: u64) -> Result<Vec<u8>, AssetError> {
+        let content = self.read(path)?;
+        let start = (start as usize).min(content.len());
+        let end = start.saturating_add(len as usize).min(content.len());
+        Ok(content[start..end].to_vec())
+    }
+}
+
+impl AssetSource for Box<dyn AssetSource> {
+    fn metadata(&self, path: &str) -> Result<AssetMetadata, AssetError> {
+        (**self).metadata(path)
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        (**self).read(path)
+    }
+
+    fn read_range(&self, path: &str, start: u64, len: u64) -> Result<Vec<u8>, AssetError> {
+        (**self).read_range(path, start, len)
+    }
+}
+
+// 规范化为以 "/" 分隔的相对路径，只接受普通路径段（"." 会被忽略），用作索引的键
+pub fn normalize_path(path: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(parts.join("/"))
+}
+
+// 从本地目录读取资源，root 为项目根目录，读取时不允许越出该目录
+pub struct FsAssetSource {
+    root: PathBuf,
+}
+
+impl FsAssetSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // 把相对路径解析为项目根目录内的真实路径
+    // 先做字面检查（拒绝绝对路径、越界的 ".."、NUL 字符），再规范化路径以识别符号链接逃逸
+    fn confined_path(&self, path: &str) -> Result<PathBuf, AssetError> {
+        if path.contains('\0') {
+            return Err(AssetError::Forbidden);
+        }
+
+        // 字面检查：".." 不能回退到项目根目录之外
+        let relative = Path::new(path);
+        let mut depth = 0usize;
+        for component in relative.components() {
+            match component {
+                Component::Normal(_) => depth += 1,
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    depth = depth.checked_sub(1).ok_or(AssetError::Forbidden)?;
+                }
+                Component::RootDir | Component::Prefix(_) => return Err(AssetError::Forbidden),
+            }
+        }
+
+        // 规范化后检查：解析符号链接后仍必须位于项目根目录内
+        let root = self.root.canonicalize()?;
+        let file_path = root.join(relative).canonicalize()?;
+        if !file_path.starts_with(&root) {
+            return Err(AssetError::Forbidden);
+        }
+
+        Ok(file_path)
+    }
+
+    // 项目根目录内的普通文件路径，目录视为不存在
+    fn file_path(&self, path: &str) -> Result<PathBuf, AssetError> {
+        // 拼接完整的文件路径，并确认没有越出项目根目录
+        let file_path = self.confined_path(path)?;
+
+        // 检查文件是否存在
+        if !file_path.is_file() {
+            return Err(AssetError::NotFound);
+        }
+
+        Ok(file_path)
+    }
+}
+
+impl AssetSource for FsAssetSource {
+    fn metadata(&self, path: &str) -> Result<AssetMetadata, AssetError> {
+        let metadata = std::fs::metadata(self.file_path(path)?)?;
+        Ok(AssetMetadata {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, AssetError> {
+        let file_path = self.file_path(path)?;
+        println!("📁 完整文件路径: {:?}", file_path);
+        Ok(std::fs::read(&file_path)?)
+    }
+
+    // 只读取需要的区间，避免把大文件整个读入内存
+    fn read_range(&self, path: &str, start: u64, len: u64) -> Result<Vec<u8>, AssetError> {
+        let file_path = self.file_path(path)?;
+        println!("📁 分段读取: {:?} [{}, +{})", file_path, start, len);
+
+        let mut file = std::fs::File::open(&file_path)?;
+        file.seek(SeekFrom::Start(start))?;
+        let mut content = Vec::with_capacity(len as usize);
+        file.take(len).read_to_end(&mut content)?;
+        Ok(content)
+    }
+}
@@ -0,0 +1,232 @@
+// 资源目录（内置项目和 projects.json 所在目录）的确定顺序：
+// 命令行参数 --resource-dir > 环境变量 MYAPP_RESOURCE_DIR > 配置文件 config.json 的 resourceDir
+// > 开发构建的源码目录 src-tauri/resources > 打包后的资源目录
+// 指定了但不可用的来源记录警告并继续尝试下一个
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config::CONFIG_FILE;
+
+pub const CLI_FLAG: &str = "--resource-dir";
+pub const ENV_VAR: &str = "MYAPP_RESOURCE_DIR";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResourceRootSource {
+    CliFlag,
+    EnvVar,
+    ConfigFile,
+    // 开发构建：编译时的 src-tauri/resources，与当前工作目录无关
+    DevSource,
+    Bundled,
+}
+
+// 最终使用的资源目录，以及解析过程中的警告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceRoot {
+    pub path: PathBuf,
+    pub source: ResourceRootSource,
+    pub warnings: Vec<String>,
+}
+
+// 各来源的候选目录
+#[derive(Debug, Default)]
+pub struct ResourceRootCandidates {
+    pub cli: Option<PathBuf>,
+    pub env: Option<PathBuf>,
+    // 配置文件中的 resourceDir
+    pub config_dir: Option<PathBuf>,
+    pub dev_dir: Option<PathBuf>,
+    // 打包后的资源目录，获取失败时为错误信息
+    pub bundled: Option<Result<PathBuf, String>>,
+}
+
+impl ResourceRootCandidates {
+    // 从当前进程的命令行参数和环境变量收集候选目录
+    pub fn from_process(config_dir: Option<PathBuf>, bundled: Result<PathBuf, String>) -> Self {
+        Self {
+            cli: cli_resource_dir(std::env::args_os().skip(1)),
+            env: std::env::var_os(ENV_VAR).filter(|value| !value.is_empty()).map(PathBuf::from),
+            config_dir,
+            dev_dir: cfg!(debug_assertions).then(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("resources")),
+            bundled: Some(bundled),
+        }
+    }
+
+    // 依次尝试各来源，返回第一个存在的目录；只有连打包后的资源目录都无法获取时返回错误
+    pub fn resolve(&self) -> Result<ResourceRoot, String> {
+        let mut warnings = Vec::new();
+        let explicit = [
+            (ResourceRootSource::CliFlag, self.cli.clone(), CLI_FLAG),
+            (ResourceRootSource::EnvVar, self.env.clone(), ENV_VAR),
+            (ResourceRootSource::ConfigFile, self.config_dir.clone(), CONFIG_FILE),
+        ];
+        for (source, path, origin) in explicit {
+            let Some(path) = path else {
+                continue;
+            };
+            if path.is_dir() {
+                return Ok(ResourceRoot {
+                    path,
+                    source,
+                    warnings,
+                });
+            }
+            warnings.push(format!("{} 指定的资源目录不存在: {:?}", origin, path));
+        }
+
+        if let Some(path) = self.dev_dir.clone().filter(|path| path.is_dir()) {
+            return Ok(ResourceRoot {
+                path,
+                source: ResourceRootSource::DevSource,
+                warnings,
+            });
+        }
+
+        // 打包后的资源目录不存在时仍然使用它（没有内置项目），用户安装的项目不受影响
+        match &self.bundled {
+            Some(Ok(path)) => {
+                if !path.is_dir() {
+                    warnings.push(format!("资源目录不存在: {:?}", path));
+                }
+                Ok(ResourceRoot {
+                    path: path.clone(),
+                    source: ResourceRootSource::Bundled,
+                    warnings,
+                })
+            }
+            Some(Err(e)) => {
+                warnings.push(format!("获取资源目录失败: {}", e));
+                Err(warnings.join("; "))
+            }
+            None => {
+                warnings.push("没有可用的资源目录".to_string());
+                Err(warnings.join("; "))
+            }
+        }
+    }
+}
+
+// 解析 --resource-dir <dir> 或 --resource-dir=<dir>，多次出现时取最后一个
+pub fn cli_resource_dir(args: impl IntoIterator<Item = OsString>) -> Option<PathBuf> {
+    let prefix = format!("{}=", CLI_FLAG);
+    let mut args = args.into_iter();
+    let mut found = None;
+    while let Some(arg) = args.next() {
+        let text = arg.to_string_lossy();
+        if text == CLI_FLAG {
+            found = args.next().map(PathBuf::from);
+        } else if let Some(value) = text.strip_prefix(&prefix) {
+            found = Some(PathBuf::from(value));
+        }
+    }
+    found.filter(|path| !path.as_os_str().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn cli_flag_forms() {
+        assert_eq!(cli_resource_dir(args(&["--resource-dir", "/a"])), Some(PathBuf::from("/a")));
+        assert_eq!(cli_resource_dir(args(&["--resource-dir=/b"])), Some(PathBuf::from("/b")));
+        // 多次出现时取最后一个
+        assert_eq!(
+            cli_resource_dir(args(&["--resource-dir", "/a", "--verbose", "--resource-dir=/c"])),
+            Some(PathBuf::from("/c"))
+        );
+        assert_eq!(cli_resource_dir(args(&["--resource-dir"])), None);
+        assert_eq!(cli_resource_dir(args(&["--resource-dir="])), None);
+        assert_eq!(cli_resource_dir(args(&["--resource-directory=/a", "/b"])), None);
+        assert_eq!(cli_resource_dir(args(&[])), None);
+    }
+
+    // 在临时目录下创建 cli、env、config、dev、bundled 五个候选目录
+    fn dirs() -> (tempfile::TempDir, [PathBuf; 5]) {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = ["cli", "env", "config", "dev", "bundled"].map(|name| dir.path().join(name));
+        for path in &paths {
+            std::fs::create_dir(path).unwrap();
+        }
+        (dir, paths)
+    }
+
+    #[test]
+    fn precedence() {
+        let (_dir, [cli, env, config, dev, bundled]) = dirs();
+        let mut candidates = ResourceRootCandidates {
+            cli: Some(cli.clone()),
+            env: Some(env.clone()),
+            config_dir: Some(config.clone()),
+            dev_dir: Some(dev.clone()),
+            bundled: Some(Ok(bundled.clone())),
+        };
+        let expected = [
+            (ResourceRootSource::CliFlag, cli),
+            (ResourceRootSource::EnvVar, env),
+            (ResourceRootSource::ConfigFile, config),
+            (ResourceRootSource::DevSource, dev),
+            (ResourceRootSource::Bundled, bundled),
+        ];
+        for (source, path) in expected {
+            let root = candidates.resolve().unwrap();
+            assert_eq!((root.source, &root.path), (source, &path));
+            assert!(root.warnings.is_empty());
+            // 去掉当前来源，下一个来源生效
+            match source {
+                ResourceRootSource::CliFlag => candidates.cli = None,
+                ResourceRootSource::EnvVar => candidates.env = None,
+                ResourceRootSource::ConfigFile => candidates.config_dir = None,
+                ResourceRootSource::DevSource => candidates.dev_dir = None,
+                ResourceRootSource::Bundled => {}
+            }
+        }
+    }
+
+    #[test]
+    fn missing_explicit_dirs_fall_through_with_warnings() {
+        let (dir, [_, _, config, _, bundled]) = dirs();
+        let candidates = ResourceRootCandidates {
+            cli: Some(dir.path().join("missing-cli")),
+            env: Some(dir.path().join("missing-env")),
+            config_dir: Some(config.clone()),
+            dev_dir: None,
+            bundled: Some(Ok(bundled)),
+        };
+        let root = candidates.resolve().unwrap();
+        assert_eq!((root.source, root.path), (ResourceRootSource::ConfigFile, config));
+        assert_eq!(root.warnings.len(), 2);
+        assert!(root.warnings[0].contains(CLI_FLAG));
+        assert!(root.warnings[1].contains(ENV_VAR));
+    }
+
+    #[test]
+    fn bundled_dir_is_the_last_resort() {
+        let dir = tempfile::tempdir().unwrap();
+        // 打包目录不存在时仍然使用，并记录警告
+        let candidates = ResourceRootCandidates {
+            bundled: Some(Ok(dir.path().join("missing"))),
+            ..Default::default()
+        };
+        let root = candidates.resolve().unwrap();
+        assert_eq!(root.source, ResourceRootSource::Bundled);
+        assert_eq!(root.warnings.len(), 1);
+
+        let candidates = ResourceRootCandidates {
+            cli: Some(dir.path().join("missing")),
+            bundled: Some(Err("no resource dir".to_string())),
+            ..Default::default()
+        };
+        let error = candidates.resolve().unwrap_err();
+        assert!(error.contains(CLI_FLAG) && error.contains("no resource dir"), "{}", error);
+        assert!(ResourceRootCandidates::default().resolve().is_err());
+    }
+}
@@ -0,0 +1,307 @@
+// 项目包签名：integrity.json 记录包内每个文件的 SHA-256，integrity.sig 是用 Ed25519 私钥对 integrity.json 原始字节的签名
+// 外壳只内置公钥，安装时校验签名和全部文件，服务时可按文件懒校验（见 protocol::integrity）
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::integrity::sha256_hex;
+use crate::protocol::FileHashes;
+
+// 文件哈希清单（位于项目包根目录）
+pub const INTEGRITY_FILE: &str = "integrity.json";
+
+// 清单签名（Base64）
+pub const SIGNATURE_FILE: &str = "integrity.sig";
+
+// 内置的签名公钥（Base64），构建时可以用 BUNDLE_SIGNING_PUBLIC_KEY 环境变量替换
+const PUBLIC_KEY: &str = match option_env!("BUNDLE_SIGNING_PUBLIC_KEY") {
+    Some(key) => key,
+    None => include_str!("../keys/bundle-signing.pub"),
+};
+
+// 签名策略
+#[derive(Debug, Clone)]
+pub struct IntegrityPolicy {
+    // 安装时拒绝没有签名的项目包；开发构建默认允许，便于本地调试
+    pub require_signature: bool,
+    // 服务时按清单校验文件内容，拒绝被篡改的文件
+    pub verify_on_serve: bool,
+}
+
+impl Default for IntegrityPolicy {
+    fn default() -> Self {
+        Self {
+            require_signature: !cfg!(debug_assertions),
+            verify_on_serve: !cfg!(debug_assertions),
+        }
+    }
+}
+
+// integrity.json 的内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BundleManifest {
+    // 相对于项目包根目录、以 "/" 分隔的路径 -> SHA-256（十六进制）
+    pub files: BTreeMap<String, String>,
+}
+
+impl BundleManifest {
+    // 以项目根目录（项目包根目录下的 root 子目录）为基准的文件哈希
+    pub fn file_hashes(&self, bundle_dir: &Path, project_root: &Path) -> FileHashes {
+        let prefix = project_root
+            .strip_prefix(bundle_dir)
+            .map(|relative| {
+                relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .unwrap_or_default();
+        let hashes: HashMap<String, String> = self
+            .files
+            .iter()
+            .filter_map(|(path, hash)| {
+                let relative = if prefix.is_empty() {
+                    Some(path.as_str())
+                } else {
+                    path.strip_prefix(&prefix).and_then(|rest| rest.strip_prefix('/'))
+                };
+                relative.map(|relative| (relative.to_string(), hash.clone()))
+            })
+            .collect();
+        FileHashes::new(hashes)
+    }
+
+    // 校验项目包内的一个目录（如模拟后端目录）：目录下每个文件都必须在清单中且内容一致
+    pub fn verify_dir(&self, bundle_dir: &Path, dir: &Path) -> Result<(), String> {
+        if !dir.starts_with(bundle_dir) {
+            return Err(format!("目录不在项目包内: {:?}", dir));
+        }
+        // 目录不存在时没有需要校验的文件
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        let hashes = self.file_hashes(bundle_dir, dir);
+        for (path, file_path) in bundle_files(dir)? {
+            let content = std::fs::read(&file_path).map_err(|e| format!("读取文件失败 {:?}: {}", file_path, e))?;
+            hashes.matches(&path, &content).map_err(|e| format!("{:?}: {}", dir, e))?;
+        }
+        Ok(())
+    }
+}
+
+// 内置公钥
+pub fn trusted_key() -> Result<VerifyingKey, String> {
+    let bytes = decode_key(PUBLIC_KEY).map_err(|e| format!("内置公钥无效: {}", e))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("内置公钥无效: {}", e))
+}
+
+// 从 Base64 读取 32 字节私钥种子
+pub fn signing_key_from_base64(encoded: &str) -> Result<SigningKey, String> {
+    decode_key(encoded)
+        .map(|bytes| SigningKey::from_bytes(&bytes))
+        .map_err(|e| format!("私钥无效: {}", e))
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; 32], String> {
+    let bytes = BASE64.decode(encoded.trim()).map_err(|e| e.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("长度应为 32 字节，实际为 {} 字节", bytes.len()))
+}
+
+// 读取并校验清单签名；没有 integrity.json 时返回 Ok(None)，有清单但签名缺失或无效时返回错误
+pub fn load_manifest(bundle_dir: &Path, key: &VerifyingKey) -> Result<Option<BundleManifest>, String> {
+    let manifest_path = bundle_dir.join(INTEGRITY_FILE);
+    let content = match std::fs::read(&manifest_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("读取签名清单失败 {:?}: {}", manifest_path, e)),
+    };
+
+    let signature_path = bundle_dir.join(SIGNATURE_FILE);
+    let signature = std::fs::read(&signature_path)
+        .map_err(|e| format!("读取签名失败 {:?}: {}", signature_path, e))?;
+    verify_manifest(&content, &signature, key)
+        .map(Some)
+        .map_err(|e| format!("{}: {:?}", e, bundle_dir))
+}
+
+// 校验清单内容的签名并解析清单；清单和签名可以来自目录或压缩包
+pub fn verify_manifest(content: &[u8], signature: &[u8], key: &VerifyingKey) -> Result<BundleManifest, String> {
+    let signature = std::str::from_utf8(signature)
+        .ok()
+        .and_then(|signature| BASE64.decode(signature.trim()).ok())
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or("签名格式无效")?;
+    key.verify(content, &signature)
+        .map_err(|_| "签名校验失败，项目包可能被篡改或不是由可信密钥签名".to_string())?;
+    serde_json::from_slice(content).map_err(|e| format!("解析签名清单失败: {}", e))
+}
+
+// 完整校验项目包：签名有效，且包内文件与清单完全一致（不多不少、哈希相同）
+// 没有签名清单时返回 Ok(None)，由调用方按策略决定是否接受
+pub fn verify_bundle(bundle_dir: &Path, key: &VerifyingKey) -> Result<Option<BundleManifest>, String> {
+    let Some(manifest) = load_manifest(bundle_dir, key)? else {
+        return Ok(None);
+    };
+
+    let files = bundle_files(bundle_dir)?;
+    for (path, file_path) in &files {
+        let expected = manifest
+            .files
+            .get(path)
+            .ok_or_else(|| format!("文件不在签名清单中: {}", path))?;
+        let content = std::fs::read(file_path).map_err(|e| format!("读取文件失败 {:?}: {}", file_path, e))?;
+        if !sha256_hex(&content).eq_ignore_ascii_case(expected) {
+            return Err(format!("文件内容与签名清单不一致: {}", path));
+        }
+    }
+    if let Some(missing) = manifest.files.keys().find(|path| !files.contains_key(*path)) {
+        return Err(format!("签名清单中的文件不存在: {}", missing));
+    }
+    Ok(Some(manifest))
+}
+
+// 生成清单并签名，写入 integrity.json 和 integrity.sig，用于打包项目
+pub fn sign_bundle(bundle_dir: &Path, key: &SigningKey) -> Result<BundleManifest, String> {
+    let mut manifest = BundleManifest::default();
+    for (path, file_path) in bundle_files(bundle_dir)? {
+        let content = std::fs::read(&file_path).map_err(|e| format!("读取文件失败 {:?}: {}", file_path, e))?;
+        manifest.files.insert(path, sha256_hex(&content));
+    }
+
+    let content = serde_json::to_vec_pretty(&manifest).map_err(|e| format!("序列化签名清单失败: {}", e))?;
+    let signature = BASE64.encode(key.sign(&content).to_bytes());
+    std::fs::write(bundle_dir.join(INTEGRITY_FILE), &content)
+        .and_then(|_| std::fs::write(bundle_dir.join(SIGNATURE_FILE), signature))
+        .map_err(|e| format!("写入签名清单失败 {:?}: {}", bundle_dir, e))?;
+    Ok(manifest)
+}
+
+// 项目包内的所有文件（不包括清单和签名本身），拒绝符号链接
+fn bundle_files(bundle_dir: &Path) -> Result<BTreeMap<String, PathBuf>, String> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![(String::new(), bundle_dir.to_path_buf())];
+    while let Some((prefix, dir)) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| format!("读取目录失败 {:?}: {}", dir, e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("读取目录失败 {:?}: {}", dir, e))?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            let file_type = entry
+                .file_type()
+                .map_err(|e| format!("读取文件类型失败 {:?}: {}", entry.path(), e))?;
+            if file_type.is_symlink() {
+                return Err(format!("项目包包含符号链接: {}", path));
+            } else if file_type.is_dir() {
+                pending.push((path, entry.path()));
+            } else if path != INTEGRITY_FILE && path != SIGNATURE_FILE {
+                files.insert(path, entry.path());
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    // 创建并签名一个项目包
+    fn signed_bundle(key: &SigningKey) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        std::fs::write(dir.path().join("index.html"), "<html></html>").unwrap();
+        std::fs::write(dir.path().join("assets/app.js"), "console.log(1)").unwrap();
+        sign_bundle(dir.path(), key).unwrap();
+        dir
+    }
+
+    #[test]
+    fn valid_signature_verifies() {
+        let key = key(1);
+        let dir = signed_bundle(&key);
+        let manifest = verify_bundle(dir.path(), &key.verifying_key()).unwrap().unwrap();
+        let files: Vec<&str> = manifest.files.keys().map(String::as_str).collect();
+        assert_eq!(files, ["assets/app.js", "index.html"]);
+        assert_eq!(manifest.files["index.html"], sha256_hex(b"<html></html>"));
+
+        let hashes = manifest.file_hashes(dir.path(), &dir.path().join("assets"));
+        assert_eq!(hashes.len(), 1);
+        assert!(hashes.get("app.js").is_some());
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        let dir = signed_bundle(&key(1));
+        let error = verify_bundle(dir.path(), &key(2).verifying_key()).unwrap_err();
+        assert!(error.contains("签名校验失败"), "{}", error);
+
+        std::fs::write(dir.path().join(SIGNATURE_FILE), "not base64").unwrap();
+        let error = verify_bundle(dir.path(), &key(1).verifying_key()).unwrap_err();
+        assert!(error.contains("签名格式无效"), "{}", error);
+
+        std::fs::remove_file(dir.path().join(SIGNATURE_FILE)).unwrap();
+        assert!(verify_bundle(dir.path(), &key(1).verifying_key()).is_err());
+    }
+
+    #[test]
+    fn modified_manifest_is_rejected() {
+        let key = key(1);
+        let dir = signed_bundle(&key);
+        let manifest_path = dir.path().join(INTEGRITY_FILE);
+        let content = std::fs::read_to_string(&manifest_path).unwrap();
+        std::fs::write(&manifest_path, content.replace("index.html", "other.html")).unwrap();
+        assert!(verify_bundle(dir.path(), &key.verifying_key()).is_err());
+    }
+
+    #[test]
+    fn modified_extra_or_missing_files_are_rejected() {
+        let key = key(1);
+
+        let dir = signed_bundle(&key);
+        std::fs::write(dir.path().join("index.html"), "<html>changed</html>").unwrap();
+        let error = verify_bundle(dir.path(), &key.verifying_key()).unwrap_err();
+        assert!(error.contains("不一致: index.html"), "{}", error);
+
+        let dir = signed_bundle(&key);
+        std::fs::write(dir.path().join("assets/extra.js"), "").unwrap();
+        let error = verify_bundle(dir.path(), &key.verifying_key()).unwrap_err();
+        assert!(error.contains("不在签名清单中: assets/extra.js"), "{}", error);
+
+        let dir = signed_bundle(&key);
+        std::fs::remove_file(dir.path().join("assets/app.js")).unwrap();
+        let error = verify_bundle(dir.path(), &key.verifying_key()).unwrap_err();
+        assert!(error.contains("不存在: assets/app.js"), "{}", error);
+    }
+
+    #[test]
+    fn unsigned_bundle_has_no_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("index.html"), "<html></html>").unwrap();
+        assert!(verify_bundle(dir.path(), &key(1).verifying_key()).unwrap().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_rejected() {
+        let key = key(1);
+        let dir = signed_bundle(&key);
+        std::os::unix::fs::symlink("/etc/hostname", dir.path().join("link")).unwrap();
+        let error = verify_bundle(dir.path(), &key.verifying_key()).unwrap_err();
+        assert!(error.contains("符号链接"), "{}", error);
+    }
+}
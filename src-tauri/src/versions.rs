@@ -0,0 +1,335 @@
+// 项目版本：已安装项目的多个版本并存于 projects/{id}/{version}/，versions.json 记录当前版本和切换历史
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+// 版本状态文件（位于 projects/{id}/ 下）
+pub const VERSIONS_FILE: &str = "versions.json";
+
+// 默认保留的版本数（包括当前版本）
+pub const DEFAULT_RETENTION: usize = 3;
+
+// 旧布局（直接安装在 projects/{id}/ 下）迁移后的版本名
+const LEGACY_VERSION: &str = "legacy";
+
+// 版本名会作为目录名，只允许字母、数字和 "." "-" "_"，且不能以 "." 开头
+pub fn is_valid_version(version: &str) -> bool {
+    !version.is_empty()
+        && !version.starts_with('.')
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionState {
+    active: Option<String>,
+    // 之前激活过的版本，从旧到新，不包括当前版本
+    history: Vec<String>,
+}
+
+// 提供给前端的版本信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectVersion {
+    pub version: String,
+    pub active: bool,
+    // 可以回滚到的版本（最近一个历史版本）
+    pub previous: bool,
+}
+
+// 一个已安装项目的版本目录
+pub struct ProjectVersions {
+    dir: PathBuf,
+    state: VersionState,
+}
+
+impl ProjectVersions {
+    // 打开项目目录；旧布局的项目会迁移为 legacy 版本
+    pub fn open(dir: &Path) -> Result<Self, String> {
+        let state_path = dir.join(VERSIONS_FILE);
+        let state = match std::fs::read_to_string(&state_path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("解析版本状态失败 {:?}: {}", state_path, e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => VersionState::default(),
+            Err(e) => return Err(format!("读取版本状态失败 {:?}: {}", state_path, e)),
+        };
+        let mut versions = Self {
+            dir: dir.to_path_buf(),
+            state,
+        };
+        if !state_path.exists() && dir.is_dir() {
+            versions.migrate_legacy()?;
+        }
+        Ok(versions)
+    }
+
+    // 是否是带版本的项目目录
+    pub fn is_versioned(dir: &Path) -> bool {
+        dir.join(VERSIONS_FILE).is_file()
+    }
+
+    // 旧布局：项目文件直接位于 dir 下，整体移动到 dir/legacy/ 并作为当前版本
+    fn migrate_legacy(&mut self) -> Result<(), String> {
+        let has_files = std::fs::read_dir(&self.dir)
+            .map_err(|e| format!("读取项目目录失败 {:?}: {}", self.dir, e))?
+            .next()
+            .is_some();
+        if !has_files {
+            return Ok(());
+        }
+        let temp = self.dir.with_file_name(format!(
+            ".legacy-{}",
+            self.dir.file_name().map(|name| name.to_string_lossy()).unwrap_or_default()
+        ));
+        std::fs::rename(&self.dir, &temp)
+            .and_then(|_| std::fs::create_dir_all(&self.dir))
+            .and_then(|_| std::fs::rename(&temp, self.dir.join(LEGACY_VERSION)))
+            .map_err(|e| format!("迁移旧版本失败 {:?}: {}", self.dir, e))?;
+        println!("🗂️ 已迁移旧布局项目: {:?} -> {}", self.dir, LEGACY_VERSION);
+        self.state.active = Some(LEGACY_VERSION.to_string());
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("创建项目目录失败 {:?}: {}", self.dir, e))?;
+        let content = serde_json::to_vec_pretty(&self.state).map_err(|e| format!("序列化版本状态失败: {}", e))?;
+        let path = self.dir.join(VERSIONS_FILE);
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content)
+            .and_then(|_| std::fs::rename(&temp_path, &path))
+            .map_err(|e| format!("写入版本状态失败 {:?}: {}", path, e))
+    }
+
+    pub fn active(&self) -> Option<&str> {
+        self.state.active.as_deref()
+    }
+
+    // 当前版本的目录
+    pub fn active_dir(&self) -> Option<PathBuf> {
+        self.active().map(|version| self.version_dir(version))
+    }
+
+    pub fn version_dir(&self, version: &str) -> PathBuf {
+        self.dir.join(version)
+    }
+
+    // 磁盘上的所有版本，按名称排序
+    pub fn installed(&self) -> Vec<String> {
+        let mut versions: Vec<String> = std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().is_dir())
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .filter(|name| is_valid_version(name))
+                    .collect()
+            })
+            .unwrap_or_default();
+        versions.sort();
+        versions
+    }
+
+    pub fn list(&self) -> Vec<ProjectVersion> {
+        let previous = self.state.history.last();
+        self.installed()
+            .into_iter()
+            .map(|version| ProjectVersion {
+                active: self.active() == Some(version.as_str()),
+                previous: previous == Some(&version),
+                version,
+            })
+            .collect()
+    }
+
+    // 切换当前版本，原来的版本记入历史
+    pub fn activate(&mut self, version: &str) -> Result<(), String> {
+        if !is_valid_version(version) || !self.version_dir(version).is_dir() {
+            return Err(format!("版本不存在: {}", version));
+        }
+        if self.active() == Some(version) {
+            return Ok(());
+        }
+        self.state.history.retain(|v| v != version);
+        if let Some(previous) = self.state.active.take() {
+            self.state.history.push(previous);
+        }
+        self.state.active = Some(version.to_string());
+        self.save()
+    }
+
+    // 回滚到上一个激活过的版本，返回回滚后的版本
+    pub fn rollback(&mut self) -> Result<String, String> {
+        // 跳过已经被删除的历史版本
+        while let Some(previous) = self.state.history.pop() {
+            if self.version_dir(&previous).is_dir() {
+                self.state.active = Some(previous.clone());
+                self.save()?;
+                return Ok(previous);
+            }
+        }
+        self.save()?;
+        Err("没有可以回滚的版本".to_string())
+    }
+
+    // 删除超出保留数量的旧版本：保留当前版本和最近激活过的版本，返回删除的版本
+    pub fn gc(&mut self, retention: usize) -> Result<Vec<String>, String> {
+        let keep_history = retention.saturating_sub(1);
+        let skip = self.state.history.len().saturating_sub(keep_history);
+        let mut keep: Vec<String> = self.state.history.split_off(skip);
+        self.state.history = keep.clone();
+        keep.extend(self.state.active.clone());
+
+        let mut removed = Vec::new();
+        for version in self.installed() {
+            if keep.contains(&version) {
+                continue;
+            }
+            let dir = self.version_dir(&version);
+            match std::fs::remove_dir_all(&dir) {
+                Ok(()) => removed.push(version),
+                Err(e) => println!("⚠️ 删除旧版本失败 {:?}: {}", dir, e),
+            }
+        }
+        self.save()?;
+        if !removed.is_empty() {
+            println!("🧹 已清理旧版本 {:?}: {:?}", self.dir, removed);
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 在项目目录下创建版本目录
+    fn install(dir: &Path, versions: &[&str]) {
+        for version in versions {
+            std::fs::create_dir_all(dir.join(version)).unwrap();
+            std::fs::write(dir.join(version).join("index.html"), *version).unwrap();
+        }
+    }
+
+    fn active_versions(versions: &ProjectVersions) -> Vec<String> {
+        versions
+            .list()
+            .into_iter()
+            .filter(|version| version.active)
+            .map(|version| version.version)
+            .collect()
+    }
+
+    #[test]
+    fn activate_switches_and_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("studio");
+        // 还没有 versions.json 时打开，已有的文件会被当作旧布局迁移
+        let mut versions = ProjectVersions::open(&project).unwrap();
+        install(&project, &["1.0.0", "1.1.0"]);
+
+        versions.activate("1.0.0").unwrap();
+        versions.activate("1.1.0").unwrap();
+        assert_eq!(versions.active(), Some("1.1.0"));
+        assert_eq!(versions.active_dir(), Some(project.join("1.1.0")));
+        assert!(ProjectVersions::is_versioned(&project));
+
+        let list = versions.list();
+        assert_eq!(active_versions(&versions), ["1.1.0"]);
+        assert!(list.iter().any(|version| version.version == "1.0.0" && version.previous));
+
+        assert!(versions.activate("2.0.0").is_err());
+        assert!(versions.activate("../1.0.0").is_err());
+        assert_eq!(versions.active(), Some("1.1.0"));
+
+        let reopened = ProjectVersions::open(&project).unwrap();
+        assert_eq!(reopened.active(), Some("1.1.0"));
+    }
+
+    #[test]
+    fn rollback_returns_to_previous_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("studio");
+        let mut versions = ProjectVersions::open(&project).unwrap();
+        install(&project, &["1.0.0", "1.1.0", "1.2.0"]);
+        for version in ["1.0.0", "1.1.0", "1.2.0"] {
+            versions.activate(version).unwrap();
+        }
+
+        // 已删除的历史版本被跳过
+        std::fs::remove_dir_all(project.join("1.1.0")).unwrap();
+        assert_eq!(versions.rollback().unwrap(), "1.0.0");
+        assert_eq!(versions.active(), Some("1.0.0"));
+        assert_eq!(ProjectVersions::open(&project).unwrap().active(), Some("1.0.0"));
+
+        let error = versions.rollback().unwrap_err();
+        assert!(error.contains("没有可以回滚的版本"), "{}", error);
+        assert_eq!(versions.active(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn rollback_with_no_previous_version_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("studio");
+        let mut versions = ProjectVersions::open(&project).unwrap();
+        install(&project, &["1.0.0"]);
+        assert!(versions.rollback().is_err());
+
+        versions.activate("1.0.0").unwrap();
+        assert!(versions.rollback().is_err());
+        assert_eq!(versions.active(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn gc_keeps_retention_and_never_deletes_the_active_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("studio");
+        let all = ["1.0.0", "1.1.0", "1.2.0", "1.3.0", "1.4.0"];
+        let mut versions = ProjectVersions::open(&project).unwrap();
+        install(&project, &all);
+        for version in all {
+            versions.activate(version).unwrap();
+        }
+        // 回滚后当前版本不是最新安装的版本
+        versions.rollback().unwrap();
+        assert_eq!(versions.active(), Some("1.3.0"));
+
+        let removed = versions.gc(3).unwrap();
+        assert_eq!(removed, ["1.0.0", "1.4.0"]);
+        assert_eq!(versions.installed(), ["1.1.0", "1.2.0", "1.3.0"]);
+
+        // 保留数量为 0 或 1 时也只删除历史版本
+        for retention in [1, 0] {
+            versions.gc(retention).unwrap();
+            assert_eq!(versions.installed(), ["1.3.0"]);
+            assert_eq!(versions.active(), Some("1.3.0"));
+        }
+        assert!(versions.rollback().is_err());
+    }
+
+    #[test]
+    fn legacy_layout_is_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("studio");
+        std::fs::create_dir_all(project.join("assets")).unwrap();
+        std::fs::write(project.join("index.html"), "<html></html>").unwrap();
+        std::fs::write(project.join("assets/app.js"), "").unwrap();
+        assert!(!ProjectVersions::is_versioned(&project));
+
+        let versions = ProjectVersions::open(&project).unwrap();
+        assert_eq!(versions.active(), Some(LEGACY_VERSION));
+        assert!(ProjectVersions::is_versioned(&project));
+        assert!(project.join("legacy/index.html").is_file());
+        assert!(project.join("legacy/assets/app.js").is_file());
+        assert!(!project.join("index.html").exists());
+        assert_eq!(versions.installed(), [LEGACY_VERSION]);
+
+        // 空目录和不存在的目录不迁移
+        let empty = dir.path().join("empty");
+        std::fs::create_dir_all(&empty).unwrap();
+        assert!(ProjectVersions::open(&empty).unwrap().active().is_none());
+        assert!(!ProjectVersions::is_versioned(&empty));
+        assert!(ProjectVersions::open(&dir.path().join("missing")).unwrap().active().is_none());
+    }
+}
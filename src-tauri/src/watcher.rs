@@ -0,0 +1,232 @@
+// 开发模式文件监听：项目文件变化（防抖）后发送 project-files-changed 事件，并刷新正在显示该项目的页面
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::discovery::ProjectCatalog;
+use crate::navigation;
+use crate::projects::{LiveReloadConfig, Project};
+use crate::protocol::ProtocolResolver;
+
+// 项目文件变化后发送，载荷为 FilesChanged
+pub const FILES_CHANGED_EVENT: &str = "project-files-changed";
+
+// 防抖时间：构建工具一次输出大量文件，合并为一次刷新
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+// 一次（防抖后的）文件变化
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesChanged {
+    pub project_id: String,
+    // 变化的文件，相对于项目根目录，以 "/" 分隔
+    pub paths: Vec<String>,
+    // 是否只有样式表变化
+    pub css_only: bool,
+}
+
+#[derive(Clone)]
+struct WatchedProject {
+    id: String,
+    root: PathBuf,
+    // 事件路径可能是解析符号链接后的真实路径
+    canonical_root: Option<PathBuf>,
+    live_reload: LiveReloadConfig,
+}
+
+impl WatchedProject {
+    fn new(project: &Project) -> Self {
+        Self {
+            id: project.id.clone(),
+            root: project.root.clone(),
+            canonical_root: project.root.canonicalize().ok(),
+            live_reload: project.live_reload.clone(),
+        }
+    }
+
+    // 变化文件相对于项目根目录的路径；根目录是压缩包文件时为文件名
+    fn relative(&self, path: &Path) -> Option<String> {
+        if path == self.root || Some(path) == self.canonical_root.as_deref() {
+            return path.file_name().map(|name| name.to_string_lossy().into_owned());
+        }
+        let relative = path
+            .strip_prefix(&self.root)
+            .ok()
+            .or_else(|| path.strip_prefix(self.canonical_root.as_ref()?).ok())?;
+        let parts: Vec<String> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        Some(parts.join("/"))
+    }
+}
+
+// 编辑器和构建工具的临时文件，忽略
+fn is_ignored(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy())
+        .is_some_and(|name| name.starts_with('.') || name.ends_with('~') || name.ends_with(".tmp"))
+}
+
+// 按项目归类变化的文件
+fn group_changes(projects: &[WatchedProject], paths: impl IntoIterator<Item = PathBuf>) -> Vec<FilesChanged> {
+    let mut changes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for path in paths {
+        if is_ignored(&path) {
+            continue;
+        }
+        // 项目根目录可能嵌套（如 versions/1.0），取最长的匹配
+        let matched = projects
+            .iter()
+            .filter_map(|project| project.relative(&path).map(|relative| (project, relative)))
+            .max_by_key(|(project, _)| project.root.as_os_str().len());
+        if let Some((project, relative)) = matched {
+            changes.entry(project.id.clone()).or_default().push(relative);
+        }
+    }
+    changes
+        .into_iter()
+        .map(|(project_id, mut paths)| {
+            paths.sort();
+            paths.dedup();
+            let css_only = paths.iter().all(|path| path.to_ascii_lowercase().ends_with(".css"));
+            FilesChanged {
+                project_id,
+                paths,
+                css_only,
+            }
+        })
+        .collect()
+}
+
+// 替换页面中对应的样式表；没有找到时整页刷新
+fn css_swap_script(paths: &[String]) -> String {
+    let paths = serde_json::to_string(paths).unwrap_or_else(|_| "[]".to_string());
+    format!(
+        r#"(() => {{
+  const changed = {};
+  const stamp = Date.now();
+  let swapped = false;
+  document.querySelectorAll('link[rel="stylesheet"]').forEach((link) => {{
+    const url = new URL(link.href, location.href);
+    const path = decodeURIComponent(url.pathname.replace(/^\/+/, ''));
+    if (changed.includes(path)) {{
+      url.searchParams.set('v', stamp);
+      link.href = url.toString();
+      swapped = true;
+    }}
+  }});
+  if (!swapped) location.reload();
+}})();"#,
+        paths
+    )
+}
+
+pub struct ProjectWatcher {
+    app_handle: AppHandle,
+    debouncer: Mutex<Option<Debouncer<RecommendedWatcher>>>,
+}
+
+impl ProjectWatcher {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            app_handle,
+            debouncer: Mutex::new(None),
+        }
+    }
+
+    // 按当前项目列表重新监听（项目列表变化后调用），内嵌项目没有可监听的目录
+    pub fn watch(&self, projects: &[Project]) {
+        let watched: Vec<WatchedProject> = projects
+            .iter()
+            .filter(|project| project.embedded.is_none() && project.root.exists())
+            .map(WatchedProject::new)
+            .collect();
+
+        let app_handle = self.app_handle.clone();
+        let handler_projects = watched.clone();
+        let debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| match result {
+            Ok(events) => {
+                let changes = group_changes(&handler_projects, events.into_iter().map(|event| event.path));
+                for change in changes {
+                    notify_changed(&app_handle, &handler_projects, change);
+                }
+            }
+            Err(e) => println!("⚠️ 文件监听出错: {}", e),
+        });
+        let mut debouncer = match debouncer {
+            Ok(debouncer) => debouncer,
+            Err(e) => {
+                println!("⚠️ 启动文件监听失败: {}", e);
+                return;
+            }
+        };
+
+        for project in &watched {
+            let mode = if project.root.is_dir() {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            if let Err(e) = debouncer.watcher().watch(&project.root, mode) {
+                println!("⚠️ 监听项目 {} 失败 {:?}: {}", project.id, project.root, e);
+            }
+        }
+        println!("👀 监听 {} 个项目的文件变化", watched.len());
+        // 旧的监听随之停止
+        *self.debouncer.lock().unwrap() = Some(debouncer);
+    }
+}
+
+// 发送事件，并刷新正在显示该项目的页面（主窗口或子 WebView）
+fn notify_changed(app_handle: &AppHandle, projects: &[WatchedProject], change: FilesChanged) {
+    println!("📝 项目 {} 文件变化: {:?}", change.project_id, change.paths);
+    if let Err(e) = app_handle.emit(FILES_CHANGED_EVENT, &change) {
+        println!("⚠️ 发送事件失败: {}", e);
+    }
+
+    let Some(project) = projects.iter().find(|project| project.id == change.project_id) else {
+        return;
+    };
+    if let Some(resolver) = app_handle.try_state::<ProtocolResolver>() {
+        // 压缩包项目重新打开，按新的内容建立索引（同时丢弃缓存）；目录项目只丢弃缓存
+        match app_handle.try_state::<ProjectCatalog>() {
+            Some(catalog) if project.root.is_file() => {
+                if let Err(e) = catalog.remount(&project.id, &resolver) {
+                    println!("⚠️ 重新挂载项目 {} 失败: {}", project.id, e);
+                }
+            }
+            _ => {
+                resolver.asset_cache().invalidate_project(&project.id);
+            }
+        }
+    }
+    if !project.live_reload.enabled {
+        return;
+    }
+    let script = if change.css_only && project.live_reload.css_hot_swap {
+        css_swap_script(&change.paths)
+    } else {
+        "location.reload();".to_string()
+    };
+    for (label, webview) in app_handle.webviews() {
+        let showing = webview
+            .url()
+            .ok()
+            .and_then(|url| navigation::project_of(&url))
+            .is_some_and(|project_id| project_id == change.project_id);
+        if !showing {
+            continue;
+        }
+        match webview.eval(&script) {
+            Ok(()) => println!("🔄 已刷新 {}: {}", label, change.project_id),
+            Err(e) => println!("⚠️ 刷新 {} 失败: {}", label, e),
+        }
+    }
+}
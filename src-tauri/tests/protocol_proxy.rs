@@ -0,0 +1,165 @@
+// myapp:// 反向代理：用本地临时 HTTP 服务器充当上游
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use tauri::http::Request;
+use tauri_app_test_lib::protocol::{FsAssetSource, ProjectMount, ProtocolResolver, ProxyRule};
+
+// 上游收到的请求
+struct Captured {
+    request_line: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+// 启动只处理一个请求的上游服务器，返回地址和收到的请求
+fn stand_in_upstream(response: &'static str, delay: Duration) -> (String, mpsc::Receiver<Captured>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_once(':').unwrap();
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+        let length: usize = headers
+            .get("content-length")
+            .map(|v| v.parse().unwrap())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let _ = sender.send(Captured {
+            request_line: request_line.trim_end().to_string(),
+            headers,
+            body,
+        });
+
+        std::thread::sleep(delay);
+        let mut stream = stream;
+        let _ = stream.write_all(response.as_bytes());
+    });
+
+    (address, receiver)
+}
+
+fn resolver(rule: ProxyRule) -> (tempfile::TempDir, ProtocolResolver) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut resolver = ProtocolResolver::new();
+    resolver.mount("studio", ProjectMount::new(FsAssetSource::new(dir.path())).proxy(vec![rule]));
+    (dir, resolver)
+}
+
+fn rule(upstream: &str) -> ProxyRule {
+    ProxyRule {
+        prefix: "/api/".to_string(),
+        upstream: upstream.to_string(),
+        rewrite: Some("/v2/".to_string()),
+        headers: HashMap::from([("Authorization".to_string(), "Bearer test-token".to_string())]),
+        timeout_ms: 2_000,
+    }
+}
+
+#[test]
+fn forwards_method_body_headers_and_rewrites_path() {
+    let (upstream, captured) = stand_in_upstream(
+        "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nX-Upstream: yes\r\nContent-Length: 11\r\nConnection: close\r\n\r\n{\"ok\":true}",
+        Duration::ZERO,
+    );
+    let (_dir, resolver) = resolver(rule(&upstream));
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("myapp://studio/api/items?draft=1")
+        .header("Content-Type", "application/json")
+        .header("X-Client", "studio")
+        .body(br#"{"name":"a"}"#.to_vec())
+        .unwrap();
+    let response = resolver.resolve(&request);
+
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.headers()["x-upstream"], "yes");
+    assert_eq!(response.body(), br#"{"ok":true}"#);
+
+    let captured = captured.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(captured.request_line, "POST /v2/items?draft=1 HTTP/1.1");
+    assert_eq!(captured.headers["authorization"], "Bearer test-token");
+    assert_eq!(captured.headers["x-client"], "studio");
+    assert_eq!(captured.headers["content-type"], "application/json");
+    assert_eq!(captured.body, br#"{"name":"a"}"#);
+}
+
+#[test]
+fn upstream_timeout_returns_504() {
+    let (upstream, _captured) = stand_in_upstream(
+        "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+        Duration::from_secs(3),
+    );
+    let mut rule = rule(&upstream);
+    rule.timeout_ms = 200;
+    let (_dir, resolver) = resolver(rule);
+
+    let request = Request::builder()
+        .uri("myapp://studio/api/slow")
+        .body(Vec::new())
+        .unwrap();
+    assert_eq!(resolver.resolve(&request).status(), 504);
+}
+
+#[test]
+fn unreachable_upstream_returns_502() {
+    // 绑定后立即释放端口，保证没有服务在监听
+    let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let (_dir, resolver) = resolver(rule(&format!("http://{}", address)));
+
+    let request = Request::builder()
+        .uri("myapp://studio/api/items")
+        .body(Vec::new())
+        .unwrap();
+    assert_eq!(resolver.resolve(&request).status(), 502);
+}
+
+#[test]
+fn unmatched_api_paths_are_not_proxied() {
+    let (_dir, resolver) = resolver(rule("http://127.0.0.1:9"));
+    let request = Request::builder()
+        .uri("myapp://studio/session/abc")
+        .body(Vec::new())
+        .unwrap();
+    assert_eq!(resolver.resolve(&request).status(), 404);
+}
+
+#[test]
+fn dot_segments_are_not_proxied() {
+    // 上游不应收到任何请求
+    let (upstream, captured) = stand_in_upstream("HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n", Duration::ZERO);
+    let (_dir, resolver) = resolver(rule(&upstream));
+
+    for uri in [
+        "myapp://studio/api/../admin",
+        "myapp://studio/api/%2e%2e/admin",
+        "myapp://studio/api/%2E%2E%2Fadmin",
+        "myapp://studio/api/.%2e/admin",
+        "myapp://studio/api/./items",
+    ] {
+        let request = Request::builder().uri(uri).body(Vec::new()).unwrap();
+        assert_eq!(resolver.resolve(&request).status(), 400, "{}", uri);
+    }
+    assert!(captured.recv_timeout(Duration::from_millis(300)).is_err());
+}
@@ -0,0 +1,117 @@
+// myapp:// 路径穿越防护：每个恶意 URL 都必须被拒绝，且不能泄露项目根目录之外的内容
+use tauri::http::Request;
+use tauri_app_test_lib::protocol::{FsAssetSource, ProtocolResolver};
+
+const SECRET: &str = "TOP-SECRET";
+
+// 构造资源目录：
+//   <tmp>/outside.txt            项目之外的机密文件
+//   <tmp>/resources/secret.txt   资源目录下、但不属于任何项目
+//   <tmp>/resources/studio/...   项目 studio
+//   <tmp>/resources/project3/... 另一个项目
+fn fixture() -> (tempfile::TempDir, ProtocolResolver) {
+    let dir = tempfile::tempdir().unwrap();
+    let resources = dir.path().join("resources");
+    std::fs::create_dir_all(resources.join("studio/assets")).unwrap();
+    std::fs::create_dir_all(resources.join("project3")).unwrap();
+    std::fs::write(dir.path().join("outside.txt"), SECRET).unwrap();
+    std::fs::write(resources.join("secret.txt"), SECRET).unwrap();
+    std::fs::write(resources.join("project3/index.html"), SECRET).unwrap();
+    std::fs::write(resources.join("studio/index.html"), "<html><head></head></html>").unwrap();
+    std::fs::write(resources.join("studio/assets/app.js"), "console.log(1)").unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::symlink;
+        symlink(dir.path().join("outside.txt"), resources.join("studio/link.txt")).unwrap();
+        symlink(dir.path(), resources.join("studio/escape")).unwrap();
+        symlink(resources.join("studio/assets"), resources.join("studio/inner")).unwrap();
+    }
+
+    let resolver = ProtocolResolver::new()
+        .with_project("studio", FsAssetSource::new(resources.join("studio")))
+        .with_project("project3", FsAssetSource::new(resources.join("project3")));
+    (dir, resolver)
+}
+
+fn get(resolver: &ProtocolResolver, uri: &str) -> (u16, String) {
+    let request = Request::builder().uri(uri).body(Vec::new()).unwrap();
+    let response = resolver.resolve(&request);
+    (
+        response.status().as_u16(),
+        String::from_utf8_lossy(response.body()).into_owned(),
+    )
+}
+
+#[test]
+fn hostile_urls_are_blocked() {
+    let (_dir, resolver) = fixture();
+    let hostile = [
+        "myapp://studio/..%2F..%2Foutside.txt",
+        "myapp://studio/..%2Fsecret.txt",
+        "myapp://studio/%2e%2e/secret.txt",
+        "myapp://studio/%2E%2E%2F%2E%2E%2Foutside.txt",
+        "myapp://studio/assets/..%2F..%2F..%2Foutside.txt",
+        "myapp://studio/..%2Fproject3%2Findex.html",
+        "myapp://studio/.%2F..%2Fsecret.txt",
+        "myapp://studio/..%2F..%2F..%2F..%2F..%2F..%2F..%2F..%2Fetc%2Fpasswd",
+        "myapp://studio/%00../secret.txt",
+    ];
+    for uri in hostile {
+        let (status, body) = get(&resolver, uri);
+        assert_eq!(status, 403, "{} 应返回 403，实际 {}", uri, status);
+        assert!(!body.contains(SECRET), "{} 泄露了项目之外的内容", uri);
+    }
+}
+
+#[test]
+fn encoded_tricks_never_leak() {
+    let (_dir, resolver) = fixture();
+    // 双重编码、反斜杠等在 Unix 上只是普通文件名，最多返回 404，但绝不能返回机密内容
+    let tricky = [
+        "myapp://studio/%252e%252e%252fsecret.txt",
+        "myapp://studio/..%5csecret.txt",
+        "myapp://studio/..%5c..%5coutside.txt",
+        "myapp://studio/%c0%ae%c0%ae/secret.txt",
+    ];
+    for uri in tricky {
+        let (status, body) = get(&resolver, uri);
+        assert_ne!(status, 200, "{} 不应成功", uri);
+        assert!(!body.contains(SECRET), "{} 泄露了项目之外的内容", uri);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_escapes_are_blocked() {
+    let (_dir, resolver) = fixture();
+    for uri in ["myapp://studio/link.txt", "myapp://studio/escape/outside.txt"] {
+        let (status, body) = get(&resolver, uri);
+        assert_eq!(status, 403, "{} 应返回 403，实际 {}", uri, status);
+        assert!(!body.contains(SECRET));
+    }
+
+    // 指向项目内部的符号链接仍然可用
+    assert_eq!(get(&resolver, "myapp://studio/inner/app.js").0, 200);
+}
+
+#[test]
+fn legitimate_paths_still_resolve() {
+    let (_dir, resolver) = fixture();
+    assert_eq!(get(&resolver, "myapp://studio/").0, 200);
+    assert_eq!(get(&resolver, "myapp://studio/assets/app.js").0, 200);
+    assert_eq!(get(&resolver, "myapp://studio/assets/..%2Findex.html").0, 200);
+    assert_eq!(get(&resolver, "myapp://studio/missing.js").0, 404);
+}
+
+#[test]
+fn unknown_hosts_are_refused() {
+    let (_dir, resolver) = fixture();
+    let (status, body) = get(&resolver, "myapp://resources/secret.txt");
+    assert_eq!(status, 404);
+    assert!(!body.contains(SECRET));
+
+    // 其他项目只能通过自己的 host 访问
+    assert_eq!(get(&resolver, "myapp://project3/").0, 200);
+    assert_eq!(get(&resolver, "myapp://studio/project3/index.html").0, 404);
+}
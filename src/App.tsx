@@ -1,14 +1,48 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import "./App.css";
-import { projects, ProjectConfig } from './projectsConfig';
+import { loadProjects, ProjectConfig } from './projectsConfig';
+
+// navigated-home 事件载荷
+interface HomeNavigation {
+  fromProject: string | null;
+  fromUrl: string | null;
+}
 
 function App() {
+  // 项目列表
+  const [projects, setProjects] = useState<ProjectConfig[]>([]);
   // 当前选中的项目
   const [currentProject, setCurrentProject] = useState<string | null>(null);
   // 加载状态
   const [loading, setLoading] = useState<string | null>(null);
 
+  // 从 Rust 侧加载项目列表，项目目录重新扫描后自动更新
+  useEffect(() => {
+    loadProjects()
+      .then(setProjects)
+      .catch((error) => console.error('❌ 加载项目列表失败:', error));
+    const unlisten = listen<ProjectConfig[]>('projects-changed', (event) => setProjects(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // 从项目返回首页后恢复选中的项目（Rust 侧 navigate_home 记录来源）
+  useEffect(() => {
+    const restore = (navigation: HomeNavigation | null) => {
+      if (navigation?.fromProject) {
+        setCurrentProject(navigation.fromProject);
+      }
+    };
+    invoke<HomeNavigation | null>('last_home_navigation').then(restore);
+    const unlisten = listen<HomeNavigation>('navigated-home', (event) => restore(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // 打开项目
   const openProject = async (project: ProjectConfig) => {
     console.log('🚀 正在切换项目:', project.name);
@@ -1,54 +1,81 @@
-// 项目配置
+import { invoke } from '@tauri-apps/api/core';
+
+// 项目配置（由 Rust 侧从 projects.json 加载并扫描项目目录自动发现，见 list_projects 命令）
 export interface ProjectConfig {
   id: string;
   name: string;
   description: string;
-  // 本地文件路径（相对于资源目录），例如 "studio/dist/index.html"
-  // 前端会使用 convertFileSrc 将其转换为 asset:// 协议的 URL
-  localPath: string;
-  windowConfig?: {
+  // 已安装项目的当前版本，内置项目为 null
+  version: string | null;
+  // 是否是签名有效的项目包
+  signed: boolean;
+  // 访问地址 myapp://{id}/，host 即项目 ID，由 Rust 侧注册表映射到项目根目录
+  url: string;
+  // 图标地址（项目目录中的 favicon.ico 等），没有时为 null
+  icon: string | null;
+  // 入口文件（相对于项目根目录），例如 "index.html"
+  entry: string;
+  window?: {
     width?: number;
     height?: number;
     resizable?: boolean;
     fullscreen?: boolean;
-  };
+  } | null;
 }
 
-export const projects: ProjectConfig[] = [
-  {
-    id: 'studio',
-    name: 'Studio 项目',
-    description: '这是 Studio 应用项目（直接加载本地文件，无需 HTTP 服务器）',
-    // 相对于资源目录的路径，指向 dist 目录下的 index.html
-    localPath: 'studio/dist/index.html',
-    windowConfig: {
-      width: 1400,
-      height: 900,
-      resizable: true
-    }
-  },
-  {
-    id: 'project2',
-    name: '项目 2',
-    description: '第二个应用项目（直接加载本地文件，无需 HTTP 服务器）',
-    // 相对于资源目录的路径
-    localPath: 'project2/dist/index.html',
-    windowConfig: {
-      width: 1200,
-      height: 800,
-      resizable: true
-    }
-  },
-  {
-    id: 'project3',
-    name: '项目 3',
-    description: '第三个应用项目（直接加载本地文件，无需 HTTP 服务器）',
-    // 相对于资源目录的路径
-    localPath: 'project3/dist/index.html',
-    windowConfig: {
-      width: 1000,
-      height: 700,
-      resizable: true
-    }
-  }
-];
+// 获取项目列表；新增项目只需放入项目目录（或修改 projects.json）
+export const loadProjects = () => invoke<ProjectConfig[]>('list_projects');
+
+// 重新扫描项目目录，结果同时通过 projects-changed 事件广播
+export const rescanProjects = () => invoke<ProjectConfig[]>('rescan_projects');
+
+// 从 zip 压缩包安装项目（解压到应用数据目录），安装后立即可以访问
+export const installProject = (zipPath: string) => invoke<ProjectConfig>('install_project', { zipPath });
+
+// 已安装项目的版本，previous 为回滚目标
+export interface ProjectVersion {
+  version: string;
+  active: boolean;
+  previous: boolean;
+}
+
+export const listProjectVersions = (projectId: string) =>
+  invoke<ProjectVersion[]>('list_project_versions', { projectId });
+
+// 切换版本 / 回滚，立即生效，结果同时通过 projects-changed 事件广播
+export const activateProjectVersion = (projectId: string, version: string) =>
+  invoke<ProjectConfig>('activate_project_version', { projectId, version });
+
+export const rollbackProject = (projectId: string) => invoke<ProjectConfig>('rollback_project', { projectId });
+
+// 开发模式下项目文件变化（project-files-changed 事件载荷），paths 相对于项目根目录
+export interface ProjectFilesChanged {
+  projectId: string;
+  paths: string[];
+  cssOnly: boolean;
+}
+
+// 启动时确定的资源目录（get_resource_root 命令）
+export interface ResourceRoot {
+  path: string;
+  source: 'cliFlag' | 'envVar' | 'configFile' | 'devSource' | 'bundled';
+  warnings: string[];
+}
+
+export const getResourceRoot = () => invoke<ResourceRoot>('get_resource_root');
+
+// 协议内存资源缓存统计（字节）
+export interface AssetCacheStats {
+  hits: number;
+  misses: number;
+  evictions: number;
+  invalidations: number;
+  entries: number;
+  size: number;
+  capacity: number;
+}
+
+export const assetCacheStats = () => invoke<AssetCacheStats>('asset_cache_stats');
+
+// 调整缓存上限（字节，0 表示不缓存），立即生效
+export const setAssetCacheSize = (size: number) => invoke<AssetCacheStats>('set_asset_cache_size', { size });